@@ -1,6 +1,6 @@
 use crate::{
     msg::{
-        UsbAdapterExecuteMsg, UsbAdapterQueryMsg
+        PublicKeyResponse, UsbAdapterExecuteMsg, UsbAdapterQueryMsg
     },
     USB_ID,
 };
@@ -9,7 +9,7 @@ use abstract_adapter::sdk::{
     features::{AccountIdentification, Dependencies, ModuleIdentification},
     AbstractSdkResult, AdapterInterface,
 };
-use abstract_adapter::std::objects::module::ModuleId;
+use abstract_adapter::std::objects::{module::ModuleId, AccountId};
 use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_std::{CosmosMsg, Deps, Uint128};
 
@@ -17,7 +17,7 @@ use cosmwasm_std::{CosmosMsg, Deps, Uint128};
 /// Interact with your adapter in other modules.
 pub trait UsbApi: AccountIdentification + Dependencies + ModuleIdentification {
     /// Construct a new adapter interface.
-    fn usb<'a>(&'a self, deps: Deps<'a>) -> UsbAdapter<Self> {
+    fn usb<'a>(&'a self, deps: Deps<'a>) -> UsbAdapter<'a, Self> {
         UsbAdapter {
             base: self,
             deps,
@@ -42,7 +42,7 @@ impl<'a, T: UsbApi> UsbAdapter<'a, T> {
     }
 
     /// returns the HUB module id
-    fn module_id(&self) -> ModuleId {
+    fn module_id(&self) -> ModuleId<'_> {
         self.module_id
     }
 
@@ -57,6 +57,11 @@ impl<'a, T: UsbApi> UsbAdapter<'a, T> {
     pub fn update_config(&self) -> AbstractSdkResult<CosmosMsg> {
         self.request(UsbAdapterExecuteMsg::UpdateConfig {})
     }
+
+    /// Register the public key the account posted on jackal
+    pub fn register_public_key(&self, key: String) -> AbstractSdkResult<CosmosMsg> {
+        self.request(UsbAdapterExecuteMsg::RegisterPublicKey { key })
+    }
 }
 
 /// Queries
@@ -71,4 +76,9 @@ impl<'a, T: UsbApi> UsbAdapter<'a, T> {
     pub fn config(&self) -> AbstractSdkResult<Uint128> {
         self.query(UsbAdapterQueryMsg::Config {})
     }
+
+    /// Query the public key registered by `account_id`
+    pub fn public_key(&self, account_id: AccountId) -> AbstractSdkResult<PublicKeyResponse> {
+        self.query(UsbAdapterQueryMsg::PublicKey { account_id })
+    }
 }
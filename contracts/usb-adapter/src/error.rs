@@ -29,4 +29,7 @@ pub enum UsbError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("invalid public key {0:?}, expected a hex encoded ECIES public key")]
+    InvalidPublicKey(String),
 }
@@ -1,7 +1,7 @@
 use crate::{
     contract::{AdapterResult, UsbAdapter},
     msg::UsbAdapterExecuteMsg,
-    state::{CONFIG, STATUS},
+    state::{CONFIG, PUBLIC_KEYS, STATUS},
     UsbError, USB_NAMESPACE,
 };
 
@@ -22,6 +22,7 @@ pub fn execute_handler(
     match msg {
         UsbAdapterExecuteMsg::UpdateConfig {} => update_config(deps, info, adapter),
         UsbAdapterExecuteMsg::SetStatus { status } => set_status(deps, adapter, status),
+        UsbAdapterExecuteMsg::RegisterPublicKey { key } => register_public_key(deps, adapter, key),
     }
 }
 
@@ -55,3 +56,17 @@ fn set_status(deps: DepsMut, adapter: UsbAdapter, status: String) -> AdapterResu
         .add_attribute("new_status", &status)
         .add_attribute("account_id", account_id.to_string()))
 }
+
+fn register_public_key(deps: DepsMut, adapter: UsbAdapter, key: String) -> AdapterResult {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(UsbError::InvalidPublicKey(key));
+    }
+    let account_registry = adapter.account_registry(deps.as_ref())?;
+
+    let account_id = account_registry.account_id(adapter.target()?)?;
+    PUBLIC_KEYS.save(deps.storage, &account_id, &key)?;
+
+    Ok(adapter
+        .response("register_public_key")
+        .add_attribute("account_id", account_id.to_string()))
+}
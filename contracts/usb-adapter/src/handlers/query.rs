@@ -1,7 +1,7 @@
 use crate::{
     contract::{AdapterResult, UsbAdapter},
-    msg::{ConfigResponse,UsbAdapterQueryMsg, StatusResponse},
-    state::{CONFIG, STATUS},
+    msg::{ConfigResponse, PublicKeyResponse, StatusResponse, UsbAdapterQueryMsg},
+    state::{CONFIG, PUBLIC_KEYS, STATUS},
 };
 
 use abstract_adapter::objects::AccountId;
@@ -18,6 +18,9 @@ pub fn query_handler(
         UsbAdapterQueryMsg::Status { account_id } => {
             to_json_binary(&query_status(deps, account_id)?)
         }
+        UsbAdapterQueryMsg::PublicKey { account_id } => {
+            to_json_binary(&query_public_key(deps, account_id)?)
+        }
     }
     .map_err(Into::into)
 }
@@ -31,3 +34,8 @@ fn query_status(deps: Deps, account_id: AccountId) -> StdResult<StatusResponse>
     let status = STATUS.may_load(deps.storage, &account_id)?;
     Ok(StatusResponse { status })
 }
+
+fn query_public_key(deps: Deps, account_id: AccountId) -> StdResult<PublicKeyResponse> {
+    let key = PUBLIC_KEYS.may_load(deps.storage, &account_id)?;
+    Ok(PublicKeyResponse { key })
+}
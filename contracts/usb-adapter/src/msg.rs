@@ -17,6 +17,11 @@ pub enum UsbAdapterExecuteMsg {
     SetStatus { status: String },
     /// Admin method: Update the configuration of the adapter
     UpdateConfig {},
+    /// Register the ECIES public key the account posted on jackal, for other accounts to share files with it
+    RegisterPublicKey {
+        /// hex.encode( ecies.PublicKey )
+        key: String,
+    },
 }
 
 /// Adapter query messages
@@ -28,6 +33,9 @@ pub enum UsbAdapterQueryMsg {
     Status { account_id: AccountId },
     #[returns(ConfigResponse)]
     Config {},
+    /// Public key registered by an account
+    #[returns(PublicKeyResponse)]
+    PublicKey { account_id: AccountId },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -37,3 +45,9 @@ pub struct ConfigResponse {}
 pub struct StatusResponse {
     pub status: Option<String>,
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct PublicKeyResponse {
+    /// hex encoded ECIES public key, `None` until the account registered one
+    pub key: Option<String>,
+}
@@ -6,3 +6,5 @@ pub struct Config {}
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STATUS: Map<&AccountId, String> = Map::new("status");
+/// ECIES public key each account posted on jackal, hex encoded
+pub const PUBLIC_KEYS: Map<&AccountId, String> = Map::new("public_keys");
@@ -1,7 +1,7 @@
 use usb_adapter::{
     contract::interface::UsbInterface,
     msg::{ConfigResponse, ExecuteMsg, UsbAdapterInstantiateMsg, UsbAdapterQueryMsgFns},
-    UsbAdapterExecuteMsg, USB_ID, USB_NAMESPACE,
+    UsbAdapterExecuteMsg, UsbError, USB_ID, USB_NAMESPACE,
 };
 
 use abstract_adapter::std::{adapter::AdapterRequestMsg, objects::namespace::Namespace};
@@ -22,7 +22,7 @@ impl TestEnv<MockBech32> {
     fn setup() -> anyhow::Result<TestEnv<MockBech32>> {
         // Create a sender and mock env
         let mock = MockBech32::new("mock");
-        let sender = mock.sender();
+        let sender = mock.sender_addr();
         let namespace = Namespace::new(USB_NAMESPACE)?;
 
         // You can set up Abstract with a builder.
@@ -143,3 +143,43 @@ fn set_status() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn register_public_key() -> anyhow::Result<()> {
+    let env = TestEnv::setup()?;
+    let adapter = env.adapter;
+
+    let subaccount = &env.publisher.account().sub_accounts()?[0];
+    let proxy = subaccount.proxy()?;
+    let register = |key: &str| {
+        subaccount.as_ref().manager.execute_on_module(
+            USB_ID,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: Some(proxy.to_string()),
+                request: UsbAdapterExecuteMsg::RegisterPublicKey {
+                    key: key.to_owned(),
+                },
+            }),
+        )
+    };
+
+    let err = register("not hex").unwrap_err();
+    assert_eq!(
+        err.root().to_string(),
+        UsbError::InvalidPublicKey("not hex".to_owned()).to_string()
+    );
+    assert_eq!(adapter.public_key(subaccount.id()?)?.key, None);
+
+    // any account can look up the key
+    register("02abcdef")?;
+    let other_account = env.abs.account_builder().build()?;
+    assert_eq!(
+        adapter
+            .call_as(&other_account.proxy()?)
+            .public_key(subaccount.id()?)?
+            .key,
+        Some("02abcdef".to_owned())
+    );
+
+    Ok(())
+}
@@ -12,6 +12,7 @@ use usb::USB_ID;
 use abstract_app::objects::namespace::Namespace;
 use abstract_client::{AbstractClient, Publisher};
 use cw_orch::{anyhow, prelude::*, tokio::runtime::Runtime};
+use usb::versions::ProtocolVersion;
 use usb_plugin::{msg::UsbInstantiateMsg, UsbInterface};
 
 const LOCAL_MNEMONIC: &str = "clip hire initial neck maid actor venue client foam budget lock catalog sweet steak waste crater broccoli pipe steak sister coyote moment obvious choose";
@@ -52,7 +53,19 @@ fn main() -> anyhow::Result<()> {
 
     let account = abstract_client.account_builder().build()?;
     // Installs the app on the Account
-    let app = account.install_app::<UsbInterface<_>>(&UsbInstantiateMsg {}, &[])?;
+    let app = account.install_app::<UsbInterface<_>>(
+        &UsbInstantiateMsg {
+            protocol_version: ProtocolVersion::V3,
+            authorized: vec![],
+            host_chain: "jackal".to_string(),
+            bech32_prefix: "jkl".to_string(),
+            payment_denom: "ujkl".to_string(),
+            storage_pricing: None,
+            renewal: None,
+            msg_limits: None,
+        },
+        &[],
+    )?;
 
     // Import app's endpoint function traits for easy interactions.
     use usb::msg::{
@@ -1,8 +1,8 @@
 use crate::{
     error::UsbError,
-    handlers,
+    handlers, ibc,
     msg::{UsbExecuteMsg, UsbInstantiateMsg, UsbMigrateMsg, UsbQueryMsg},
-    replies::{self, INSTANTIATE_REPLY_ID, JACKAL_MSG_REPLY_ID},
+    replies::{self, INSTANTIATE_REPLY_ID, JACKAL_MSG_REPLY_ID, KEY_REGISTRATION_REPLY_ID},
     APP_VERSION, USB_ID,
 };
 
@@ -21,9 +21,15 @@ const APP: Usb = Usb::new(USB_ID, APP_VERSION, None)
     .with_query(handlers::query_handler)
     .with_migrate(handlers::migrate_handler)
     .with_dependencies(&[])
+    .with_ibc_callbacks(&[
+        (ibc::DISPATCH_CALLBACK_ID, ibc::dispatch_callback),
+        (ibc::QUERY_CALLBACK_ID, ibc::query_callback),
+    ])
+    .with_module_ibc(ibc::module_ibc_handler)
     .with_replies(&[
         (INSTANTIATE_REPLY_ID, replies::instantiate_reply),
         (JACKAL_MSG_REPLY_ID, replies::jackal_reply),
+        (KEY_REGISTRATION_REPLY_ID, replies::key_registration_reply),
     ]);
 
 // Export handlers
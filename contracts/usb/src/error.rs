@@ -2,10 +2,12 @@ use abstract_app::objects::version_control::VersionControlError;
 use abstract_app::sdk::AbstractSdkError;
 use abstract_app::std::AbstractError;
 use abstract_app::AppError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError, Uint128};
 use cw_asset::AssetError;
 use cw_controllers::AdminError;
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
+use usb::{error::JackalError, JackalMsgKind};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum UsbError {
@@ -23,12 +25,164 @@ pub enum UsbError {
     #[error("not-implemented")]
     NotImplemented(),
 
+    #[error("{0}")]
+    Jackal(#[from] JackalError),
+
+    #[error("{sender} is not authorized to send jackal msgs for this account")]
+    Unauthorized { sender: String },
+
+    #[error("{sender} is not authorized to send {kind} msgs for this account")]
+    UnauthorizedMsg { sender: String, kind: JackalMsgKind },
+
+    #[error("{sender} is not authorized to query jackal for this account")]
+    UnauthorizedQuery { sender: String },
+
+    #[error("host chain {0} is not registered on the ibc-client")]
+    HostNotRegistered(String),
+
+    #[error("invalid bech32 prefix {0:?}")]
+    InvalidBech32Prefix(String),
+
+    #[error("invalid denom {0:?}")]
+    InvalidDenom(String),
+
+    #[error("remote proxy {address} doesn't use the configured bech32 prefix {prefix}")]
+    RemoteProxyPrefix { address: String, prefix: String },
+
+    #[error("no remote proxy registered for this account on {0}")]
+    RemoteProxyNotFound(String),
+
+    #[error("storage costs {required}{denom} but the account only has {available}{denom}")]
+    InsufficientFunds {
+        denom: String,
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("storage renewal requires a storage pricing")]
+    RenewalWithoutPricing {},
+
+    #[error("invalid renewal policy: {0}")]
+    InvalidRenewalPolicy(String),
+
+    #[error("storage renewal costs {cost}, over the maximum spend of {max_spend}")]
+    RenewalOverBudget { cost: Uint128, max_spend: Uint128 },
+
+    #[error("the account has no storage plan")]
+    NoStoragePlan {},
+
+    #[error("listing of {bytes} bytes, on top of {subleased} bytes listed or leased, exceeds the storage plan of {plan_bytes} bytes")]
+    ListingExceedsPlan {
+        bytes: i64,
+        subleased: i64,
+        plan_bytes: i64,
+    },
+
+    #[error("listing {listing_id} has {available} bytes available, {requested} requested")]
+    LeaseUnavailable {
+        listing_id: u64,
+        requested: i64,
+        available: i64,
+    },
+
+    #[error("listings must allow leases of at least 1 day, got {0} days")]
+    ListingDuration(i64),
+
+    #[error("lease duration must be between 1 and {max} days, got {requested}")]
+    LeaseDuration { requested: i64, max: i64 },
+
+    #[error("lease would end after the storage plan expires")]
+    LeaseOutlivesPlan {},
+
+    #[error("lease costs {expected}, received {received}{}", expected.denom)]
+    LeasePayment { expected: Coin, received: Uint128 },
+
+    #[error("lease {0} can only be cancelled once its storage purchase failed")]
+    LeaseNotCancellable(u64),
+
+    #[error("operation {id} bought lease {lease}, which was cancelled and refunded")]
+    LeaseCancelled { id: u64, lease: u64 },
+
+    #[error("{0} is not a jackal address")]
+    InvalidJackalAddress(String),
+
+    #[error("{0} is not in the file index")]
+    PathNotIndexed(String),
+
+    #[error("{0} is already in the file index")]
+    PathExists(String),
+
+    #[error("folder {0} is not in the file index")]
+    MissingParent(String),
+
+    #[error("{path} is not shared with {recipient}")]
+    NotShared { path: String, recipient: String },
+
+    #[error("can't move or copy {from} into itself, to {to}")]
+    IntoItself { from: String, to: String },
+
+    #[error("invalid msg limits: {0}")]
+    InvalidMsgLimits(String),
+
+    #[error("msg {index} buys {bytes} bytes, outside the limits of {min} to {}", max.map_or("unlimited".to_string(), |max| max.to_string()))]
+    StorageBytes {
+        index: usize,
+        bytes: i64,
+        min: i64,
+        max: Option<i64>,
+    },
+
+    #[error("msg {index} buys storage for {duration_days} days, outside the limits of {min} to {}", max.map_or("unlimited".to_string(), |max| max.to_string()))]
+    StorageDuration {
+        index: usize,
+        duration_days: i64,
+        min: i64,
+        max: Option<i64>,
+    },
+
+    #[error("msg {index} pays for storage with {denom:?}, which is not an accepted denom")]
+    UnknownPaymentDenom { index: usize, denom: String },
+
+    #[error("msg {index} buys storage for {address}, which is not a jackal address")]
+    InvalidStorageAddress { index: usize, address: String },
+
+    #[error("msg {index} has an empty cid")]
+    EmptyCid { index: usize },
+
+    #[error("msg {index} posts the public key {key:?}, which is not hex encoded")]
+    InvalidPublicKey { index: usize, key: String },
+
+    #[error("msg {index} posts {path}, which has no parent folder to hash")]
+    InvalidHashParent { index: usize, path: String },
+
+    #[error("msg {index} has a {field} of {value:?}, not a 64 character hex hash")]
+    InvalidHash {
+        index: usize,
+        field: String,
+        value: String,
+    },
+
+    #[error("operation {0} already succeeded")]
+    OperationSucceeded(u64),
+
+    #[error("operation {0} is still pending")]
+    OperationPending(u64),
+
+    #[error("operation {id} was already retried {max} times")]
+    RetryLimit { id: u64, max: u32 },
+
     #[error("{0}")]
     Asset(#[from] AssetError),
 
     #[error("{0}")]
     Admin(#[from] AdminError),
 
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("{0}")]
     DappError(#[from] AppError),
 }
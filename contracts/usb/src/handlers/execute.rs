@@ -1,254 +1,1013 @@
 use crate::{
     contract::{Usb, UsbResult},
-    msg::UsbExecuteMsg,
+    handlers::{
+        files::{self, apply_file_changes, file_changes},
+        keys::{cache_key, posted_key},
+        remote_queries,
+        stats::{purchased_bytes, record_dispatch, record_purchase, record_retry},
+        sublease,
+        validation::{validate_msg_limits, validate_msgs},
+    },
+    ibc::DISPATCH_CALLBACK_ID,
+    msg::{UsbExecuteMsg, UsbIbcMsg},
     replies::JACKAL_MSG_REPLY_ID,
-    state::COUNT,
-    UsbError,
+    state::{
+        Authorization, Caller, Config, FileChange, MsgLimits, Operation, OperationStatus,
+        RenewalPolicy, StoragePricing, StoragePurchase, CONFIG, DISPATCHING, FILE_CHANGES,
+        KEY_POSTS, MAX_RETRIES, NEXT_OPERATION_ID, OPERATIONS, OPERATION_MSGS, PURCHASED_BYTES,
+        REMOTE_PROXIES, RENEWAL, STORAGE_PLAN, STORAGE_PURCHASES,
+    },
+    UsbError, USB_ID,
 };
 
 use abstract_app::{
-    objects::chain_name::ChainName,
-    sdk::{query_ownership, Execution, IbcInterface},
-    std::{ibc_client, ibc_host::HostAction, manager, proxy, IBC_CLIENT, PROXY},
+    objects::{chain_name::ChainName, module::ModuleInfo},
+    sdk::{features::AccountIdentification, Execution, IbcInterface, ModuleInterface},
+    std::{ibc::CallbackInfo, ibc_client},
     traits::AbstractResponse,
 };
-use abstract_std::JUNO;
 use cosmwasm_std::{
-    to_json_binary, wasm_execute, Addr, CosmosMsg, DepsMut, Empty, Env, MessageInfo,
+    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, ReplyOn,
+    StdError, StdResult, Storage, SubMsg, Uint128,
 };
-use prost::Message;
+use sha2::{Digest, Sha256};
 use usb::{
-    helpers::{hash_and_hex, merkle_helper},
-    types::{
-        filetree::{MsgAddViewers, MsgDeleteViewers, MsgPostFile, MsgPostKey},
-        storage::{MsgBuyStorage, MsgCancelContract, MsgSignContract, MsgUpgradeStorage},
-    },
-    JackalMsg,
+    helpers::{hash_and_hex, owner_address, AccessMap, FilePath},
+    versions::ProtocolVersion,
+    JackalMsg, JackalMsgKind,
 };
 
 pub fn execute_handler(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     app: Usb,
     msg: UsbExecuteMsg,
 ) -> UsbResult {
-    // only admin can run this
-    // app.admin.assert_admin(deps.as_ref(), &info.sender)?;
     match msg {
-        UsbExecuteMsg::JackalMsgs { msgs } => send_content(deps, info, msgs, app),
-    }
-}
-// content workflow: manager -> usb -> proxy -> ibc-client -> note -> (ibc) -> voice -> proxy -> ibc-host -> jackal
-fn send_content(deps: DepsMut, info: MessageInfo, msgs: Vec<JackalMsg>, mut app: Usb) -> UsbResult {
-    let mut jackal_msgs = vec![];
-    // api for executing account actions as module
-    let executor = app.executor(deps.as_ref());
-
-    for msg in msgs {
-        // define msgs to send to jackal as account
-        let msg = match msg {
-            JackalMsg::MakeRoot {
-                editors,
-                viewers,
-                tracking_number,
-            } => {
-                let make_root_type = String::from("/canine_chain.filetree.MsgMakeRootV2");
-                let msg_make_root = usb::types::filetree::MsgMakeRootV2 {
-                    creator: info.sender.to_string(), //todo: change to account manager or proxy
-                    viewers,
-                    editors,
-                    tracking_number,
-                };
-                let encoded_root_msg = msg_make_root.encode_to_vec();
-                let make_root_msg: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: make_root_type.clone(),
-                    value: cosmwasm_std::Binary(encoded_root_msg.to_vec()),
-                };
-
-                Ok(make_root_msg)
-            }
-            JackalMsg::PostFile {
-                hash_parent,
-                hash_child,
-                contents,
-                viewers,
-                editors,
-                tracking_number,
-            } => {
-                let post_file_type = String::from("/canine_chain.filetree.MsgPostFile");
-                let msg_post_file = MsgPostFile {
-                    creator: info.sender.to_string(),
-                    account: hash_and_hex(&info.sender.to_string()), // todo: derive from manager
-                    hash_parent,
-                    hash_child,
-                    contents,
-                    viewers,
-                    editors,
-                    tracking_number,
-                };
-                let encoded_file = msg_post_file.encode_to_vec();
-                let file: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: post_file_type.clone(),
-                    value: cosmwasm_std::Binary(encoded_file.to_vec()),
-                };
-                Ok(file)
-            }
-            JackalMsg::AddViewers {
-                viewer_ids,
-                viewer_keys,
-                address,
-                owner,
-            } => {
-                let add_viewers_type = String::from("/canine_chain.filetree.MsgAddViewers");
-                let msg_add_viewers = MsgAddViewers {
-                    creator: info.sender.to_string(),
-                    viewer_ids,
-                    viewer_keys,
-                    address,
-                    owner,
-                };
-                let encoded_add_viewers = msg_add_viewers.encode_to_vec();
-                let add_viewers = CosmosMsg::Stargate {
-                    type_url: add_viewers_type,
-                    value: cosmwasm_std::Binary(encoded_add_viewers),
-                };
-                Ok(add_viewers)
-            }
-            JackalMsg::DeleteViewers {
-                viewer_ids,
-                address,
-                owner,
-            } => {
-                let delete_viewers_type = String::from("/canine_chain.filetree.MsgRemoveViewers");
-                let msg_delete_viewers = MsgDeleteViewers {
-                    creator: info.sender.to_string(),
-                    viewer_ids,
-                    address,
-                    owner,
-                };
-                let encoded_add_viewers = msg_delete_viewers.encode_to_vec();
-                let delete_viewers = CosmosMsg::Stargate {
-                    type_url: delete_viewers_type,
-                    value: cosmwasm_std::Binary(encoded_add_viewers),
-                };
-                Ok(delete_viewers)
-            }
-            JackalMsg::SignContract { cid } => {
-                let sign_contract_type = String::from("/canine_chain.storage.MsgSignContract");
-                let msg_sign_contract = MsgSignContract {
-                    creator: info.sender.to_string(),
-                    cid,
-                };
-                let encoded_sign_contract = msg_sign_contract.encode_to_vec();
-                let sign_contract: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: sign_contract_type.clone(),
-                    value: cosmwasm_std::Binary(encoded_sign_contract.to_vec()),
-                };
-                Ok(sign_contract)
+        UsbExecuteMsg::JackalMsgs { msgs } => {
+            let kinds: Vec<JackalMsgKind> = msgs.iter().map(JackalMsg::kind).collect();
+            assert_authorized(deps.as_ref(), &app, &info.sender, &kinds)?;
+            send_content(deps, env, info, msgs, app)
+        }
+        UsbExecuteMsg::UploadFile {
+            path,
+            fid,
+            viewers,
+            editors,
+            size,
+        } => upload_file(deps, env, info, app, path, fid, viewers, editors, size),
+        UsbExecuteMsg::MakeDir { path, parents } => {
+            files::make_dir(deps, env, info, app, path, parents)
+        }
+        UsbExecuteMsg::Move { from, to } => files::relocate(deps, env, info, app, from, to, false),
+        UsbExecuteMsg::Copy { from, to } => files::relocate(deps, env, info, app, from, to, true),
+        UsbExecuteMsg::ShareFile {
+            path,
+            recipient,
+            wrapped_key,
+        } => files::share_file(deps, env, info, app, path, recipient, wrapped_key),
+        UsbExecuteMsg::RevokeShare { path, recipient } => {
+            files::revoke_share(deps, env, info, app, path, recipient)
+        }
+        UsbExecuteMsg::RetryOperation { id } => retry_operation(deps, env, info, app, id),
+        UsbExecuteMsg::QueryJackal { query } => {
+            remote_queries::query_jackal(deps, env, info, app, query)
+        }
+        UsbExecuteMsg::UpdateConfig {
+            protocol_version,
+            authorized,
+            host_chain,
+            bech32_prefix,
+            payment_denom,
+            storage_pricing,
+            renewal,
+            msg_limits,
+        } => update_config(
+            deps,
+            info,
+            app,
+            protocol_version,
+            authorized,
+            host_chain,
+            bech32_prefix,
+            payment_denom,
+            storage_pricing,
+            renewal,
+            msg_limits,
+        ),
+        UsbExecuteMsg::Tick {} => tick(deps, env, info, app),
+        UsbExecuteMsg::CreateListing {
+            bytes,
+            denom,
+            price_per_gb_month,
+            max_duration_days,
+        } => sublease::create_listing(
+            deps,
+            env,
+            info,
+            app,
+            bytes,
+            denom,
+            price_per_gb_month,
+            max_duration_days,
+        ),
+        UsbExecuteMsg::CloseListing { id } => sublease::close_listing(deps, info, app, id),
+        UsbExecuteMsg::TakeLease {
+            listing_id,
+            bytes,
+            duration_days,
+            for_address,
+        } => sublease::take_lease(
+            deps,
+            env,
+            info,
+            app,
+            listing_id,
+            bytes,
+            duration_days,
+            for_address,
+        ),
+        UsbExecuteMsg::CancelLease { id } => sublease::cancel_lease(deps, info, app, id),
+    }
+}
+
+/// Update the configuration of the app
+#[allow(clippy::too_many_arguments)]
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    app: Usb,
+    protocol_version: Option<ProtocolVersion>,
+    authorized: Option<Vec<Authorization>>,
+    host_chain: Option<String>,
+    bech32_prefix: Option<String>,
+    payment_denom: Option<String>,
+    storage_pricing: Option<StoragePricing>,
+    renewal: Option<RenewalPolicy>,
+    msg_limits: Option<MsgLimits>,
+) -> UsbResult {
+    // Only the admin should be able to call this
+    app.admin.assert_admin(deps.as_ref(), &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(protocol_version) = protocol_version {
+        config.protocol_version = protocol_version;
+    }
+    if let Some(authorized) = authorized {
+        config.authorized = validate_authorized(deps.as_ref(), authorized)?;
+    }
+    if let Some(host_chain) = host_chain {
+        let host_chain = ChainName::from_string(host_chain)?;
+        assert_host_registered(deps.as_ref(), &app, &host_chain)?;
+        config.host_chain = host_chain;
+    }
+    if let Some(bech32_prefix) = bech32_prefix {
+        config.bech32_prefix = validate_bech32_prefix(bech32_prefix)?;
+    }
+    if let Some(payment_denom) = payment_denom {
+        config.payment_denom = validate_denom(payment_denom)?;
+    }
+    if let Some(storage_pricing) = storage_pricing {
+        config.storage_pricing = Some(validate_storage_pricing(storage_pricing)?);
+    }
+    if let Some(renewal) = renewal {
+        config.renewal = Some(renewal);
+        // a new policy releases a failed renewal, the next tick issues it again under the new policy
+        if let Some(id) = RENEWAL.may_load(deps.storage)? {
+            if OPERATIONS.load(deps.storage, id)?.status != OperationStatus::Pending {
+                RENEWAL.remove(deps.storage);
             }
-            JackalMsg::CancelContract { cid } => {
-                let cancel_contract_type = String::from("/canine_chain.storage.MsgCancelContract");
-                let msg_cancel_contract = MsgCancelContract {
-                    creator: info.sender.to_string(),
-                    cid,
-                };
-
-                let encoded_cancel_contract = msg_cancel_contract.encode_to_vec();
-                let cancel_contract: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: cancel_contract_type,
-                    value: cosmwasm_std::Binary(encoded_cancel_contract),
-                };
-                Ok(cancel_contract)
+        }
+    }
+    if let Some(msg_limits) = msg_limits {
+        config.msg_limits = validate_msg_limits(msg_limits)?;
+    }
+    validate_renewal(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(app.response("update_config"))
+}
+/// Error unless the ibc-client of the account has an ibc connection to `host_chain`
+fn assert_host_registered(deps: Deps, app: &Usb, host_chain: &ChainName) -> UsbResult<()> {
+    let ibc_client = app.ibc_client(deps).module_address()?;
+    deps.querier
+        .query_wasm_smart::<ibc_client::HostResponse>(
+            ibc_client,
+            &ibc_client::QueryMsg::Host {
+                chain_name: host_chain.to_string(),
+            },
+        )
+        .map_err(|_| UsbError::HostNotRegistered(host_chain.to_string()))?;
+    Ok(())
+}
+
+/// bech32 prefixes are lowercase alphanumeric, ex: `jkl`
+pub(crate) fn validate_bech32_prefix(prefix: String) -> UsbResult<String> {
+    if prefix.is_empty()
+        || !prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        return Err(UsbError::InvalidBech32Prefix(prefix));
+    }
+    Ok(prefix)
+}
+
+/// native or ibc denom, ex: `ujkl` or `ibc/...`
+pub(crate) fn validate_denom(denom: String) -> UsbResult<String> {
+    if denom.len() < 3
+        || !denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+    {
+        return Err(UsbError::InvalidDenom(denom));
+    }
+    Ok(denom)
+}
+
+pub(crate) fn validate_storage_pricing(pricing: StoragePricing) -> UsbResult<StoragePricing> {
+    Ok(StoragePricing {
+        funding_denom: validate_denom(pricing.funding_denom)?,
+        ..pricing
+    })
+}
+
+/// A renewal is capped in the funding denom, so it needs a storage pricing.
+/// It must renew for at least a day, and not before the renewed plan would itself be due.
+pub(crate) fn validate_renewal(config: &Config) -> UsbResult<()> {
+    let Some(renewal) = &config.renewal else {
+        return Ok(());
+    };
+    if config.storage_pricing.is_none() {
+        return Err(UsbError::RenewalWithoutPricing {});
+    }
+    if renewal.duration_days <= 0 {
+        return Err(UsbError::InvalidRenewalPolicy(
+            "duration_days must be positive".to_string(),
+        ));
+    }
+    if renewal.days_before_expiry > renewal.duration_days as u64 {
+        return Err(UsbError::InvalidRenewalPolicy(
+            "days_before_expiry is longer than duration_days".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the addresses of the allowed callers
+pub(crate) fn validate_authorized(
+    deps: Deps,
+    authorized: Vec<Authorization>,
+) -> UsbResult<Vec<Authorization>> {
+    authorized
+        .into_iter()
+        .map(|authorization| {
+            let caller = match authorization.caller {
+                Caller::Address(address) => {
+                    Caller::Address(deps.api.addr_validate(&address)?.into_string())
+                }
+                module => module,
+            };
+            Ok(Authorization {
+                caller,
+                ..authorization
+            })
+        })
+        .collect()
+}
+
+/// The admin may send any msg.
+/// Other callers need an authorization covering each of the msg `kinds`.
+pub(crate) fn assert_authorized(
+    deps: Deps,
+    app: &Usb,
+    sender: &Addr,
+    kinds: &[JackalMsgKind],
+) -> UsbResult<()> {
+    let Some(authorizations) = authorizations(deps, app, sender)? else {
+        return Ok(());
+    };
+    if let Some(kind) = kinds
+        .iter()
+        .find(|kind| !authorizations.iter().any(|auth| auth.allows(**kind)))
+    {
+        return Err(UsbError::UnauthorizedMsg {
+            sender: sender.to_string(),
+            kind: *kind,
+        });
+    }
+
+    Ok(())
+}
+
+/// The admin may query jackal, other callers need an authorization covering queries
+pub(crate) fn assert_query_authorized(deps: Deps, app: &Usb, sender: &Addr) -> UsbResult<()> {
+    let Some(authorizations) = authorizations(deps, app, sender)? else {
+        return Ok(());
+    };
+    if !authorizations.iter().any(Authorization::allows_queries) {
+        return Err(UsbError::UnauthorizedQuery {
+            sender: sender.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Authorizations of `sender`, `None` for the admin who needs none
+fn authorizations(deps: Deps, app: &Usb, sender: &Addr) -> UsbResult<Option<Vec<Authorization>>> {
+    if app.admin.is_admin(deps, sender)? {
+        return Ok(None);
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let modules = app.modules(deps);
+    let authorizations: Vec<Authorization> = config
+        .authorized
+        .into_iter()
+        .filter(|authorization| match &authorization.caller {
+            Caller::Address(address) => address == sender.as_str(),
+            // modules that are not installed on the account can't be the caller
+            Caller::Module(module_id) => modules
+                .module_address(module_id)
+                .is_ok_and(|module| module == sender),
+        })
+        .collect();
+    if authorizations.is_empty() {
+        return Err(UsbError::Unauthorized {
+            sender: sender.to_string(),
+        });
+    }
+
+    Ok(Some(authorizations))
+}
+
+/// Post `fid` at `path`, under a freshly generated tracking number.
+/// The account hash is derived from the remote proxy signing the msg.
+#[allow(clippy::too_many_arguments)]
+fn upload_file(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    path: FilePath,
+    fid: String,
+    viewers: AccessMap,
+    editors: AccessMap,
+    size: Option<u64>,
+) -> UsbResult {
+    assert_authorized(
+        deps.as_ref(),
+        &app,
+        &info.sender,
+        &[JackalMsgKind::PostFile],
+    )?;
+    let tracking_number = tracking_number(&env, &path);
+    let file = FileChange::Post {
+        path: path.clone(),
+        fid: fid.clone(),
+        tracking_number: tracking_number.clone(),
+        size,
+        viewers: viewers.clone(),
+        editors: editors.clone(),
+    };
+    let msg = JackalMsg::PostFile {
+        path,
+        contents: fid,
+        viewers,
+        editors,
+        tracking_number,
+    };
+    send_indexed_content(deps, env, info, vec![msg], vec![file], app)
+}
+
+/// UUID formatted tracking number, unique per app, block, tx and path
+pub(crate) fn tracking_number(env: &Env, path: &FilePath) -> String {
+    let tx_index = env
+        .transaction
+        .as_ref()
+        .map(|tx| tx.index)
+        .unwrap_or_default();
+    let mut hash: [u8; 16] = Sha256::new()
+        .chain_update(env.contract.address.as_bytes())
+        .chain_update(env.block.height.to_be_bytes())
+        .chain_update(tx_index.to_be_bytes())
+        .chain_update(path.as_str().as_bytes())
+        .finalize()[..16]
+        .try_into()
+        .expect("sha256 is 32 bytes");
+    // version 4, variant 1
+    hash[6] = (hash[6] & 0x0f) | 0x40;
+    hash[8] = (hash[8] & 0x3f) | 0x80;
+    let hex = hex::encode(hash);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+// content workflow: manager -> usb -> ibc-client -> note -> (ibc) -> voice -> proxy -> ibc-host -> usb -> proxy -> jackal
+// when the account lives on jackal: manager -> usb -> proxy -> jackal
+pub(crate) fn send_content(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<JackalMsg>,
+    app: Usb,
+) -> UsbResult {
+    let files = file_changes(&msgs);
+    send_indexed_content(deps, env, info, msgs, files, app)
+}
+
+/// Send `msgs` to jackal, applying `files` to the file index once they succeeded
+pub(crate) fn send_indexed_content(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<JackalMsg>,
+    files: Vec<FileChange>,
+    app: Usb,
+) -> UsbResult {
+    dispatch_content(deps, env, info, vec![], msgs, files, app)
+}
+
+/// Send `msgs` to jackal once `deposit`, sent along but kept by the account, reached its proxy.
+/// The deposit can pay for the storage bought by `msgs`.
+pub(crate) fn send_deposited_content(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    deposit: Vec<Coin>,
+    msgs: Vec<JackalMsg>,
+    app: Usb,
+) -> UsbResult {
+    let files = file_changes(&msgs);
+    dispatch_content(deps, env, info, deposit, msgs, files, app)
+}
+
+fn dispatch_content(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    deposit: Vec<Coin>,
+    msgs: Vec<JackalMsg>,
+    files: Vec<FileChange>,
+    app: Usb,
+) -> UsbResult {
+    let config = CONFIG.load(deps.storage)?;
+    let host_chain = &config.host_chain;
+    let local = is_local(&env, &config);
+    // jackal msgs are signed by the accounts proxy on jackal, created by the ibc-host if the account is remote
+    let creator = if local {
+        app.proxy_address(deps.as_ref())?.into_string()
+    } else {
+        remote_proxy(deps.branch(), &app, &config)?
+    };
+
+    let msgs: Vec<JackalMsg> = msgs
+        .into_iter()
+        .map(|msg| with_defaults(msg, &config.payment_denom, &creator))
+        .collect();
+    validate_msgs(&config, &msgs)?;
+    let purchase = storage_purchase(&msgs, &creator);
+    let bytes = purchased_bytes(&msgs);
+    let key = posted_key(&msgs);
+
+    // funds moved to the remote proxy, topped up from the account to pay for the storage bought by the batch
+    let transfer = match &config.storage_pricing {
+        Some(pricing) if !local => {
+            storage_funding(deps.as_ref(), &app, pricing, &msgs, &info.funds, &deposit)?
+        }
+        _ => info.funds.clone(),
+    };
+    // the deposit goes first, so the account holds it when the batch is paid for
+    let deposit = if deposit.is_empty() {
+        None
+    } else {
+        Some(BankMsg::Send {
+            to_address: app.proxy_address(deps.as_ref())?.into_string(),
+            amount: deposit,
+        })
+    };
+
+    // define msgs to send to jackal as account, in the shape of the configured canine-chain version
+    let kinds: Vec<JackalMsgKind> = msgs.iter().map(JackalMsg::kind).collect();
+    let jackal_msgs = msgs
+        .into_iter()
+        .map(|msg| msg.into_cosmos_msg(config.protocol_version, &creator))
+        .collect::<Result<Vec<CosmosMsg>, _>>()?;
+
+    if local {
+        // the batch is atomic, if the tx goes through every msg succeeded
+        let id = start_operation(
+            deps.storage,
+            &env,
+            kinds.clone(),
+            &jackal_msgs,
+            OperationStatus::Succeeded,
+        )?;
+        record_dispatch(
+            deps.storage,
+            &env,
+            id,
+            &kinds,
+            &info.funds,
+            OperationStatus::Succeeded,
+        )?;
+        record_purchase(deps.storage, bytes)?;
+        if let Some(purchase) = purchase {
+            STORAGE_PLAN.save(deps.storage, &purchase.plan(env.block.time))?;
+        }
+        apply_file_changes(deps.storage, files, env.block.height)?;
+        let register_key = match key {
+            Some(key) => cache_key(deps.branch(), &env, &app, key)?,
+            None => None,
+        };
+        if !jackal_msgs.is_empty() {
+            DISPATCHING.save(deps.storage, &id)?;
+        }
+        // the proxy broadcasts the msgs itself and pays with the funds sent along
+        let mut response = app
+            .response("send_content")
+            .add_attribute("operation_id", id.to_string())
+            .add_messages(deposit)
+            .add_submessages(broadcast(deps.as_ref(), &app, jackal_msgs)?)
+            .add_submessages(register_key);
+        if !info.funds.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: creator,
+                amount: info.funds,
+            });
+        }
+        return Ok(response);
+    }
+
+    let id = start_operation(
+        deps.storage,
+        &env,
+        kinds.clone(),
+        &jackal_msgs,
+        OperationStatus::Pending,
+    )?;
+    record_dispatch(
+        deps.storage,
+        &env,
+        id,
+        &kinds,
+        &transfer,
+        OperationStatus::Pending,
+    )?;
+    if !bytes.is_zero() {
+        PURCHASED_BYTES.save(deps.storage, id, &bytes)?;
+    }
+    if let Some(purchase) = purchase {
+        STORAGE_PURCHASES.save(deps.storage, id, &purchase)?;
+    }
+    if !files.is_empty() {
+        FILE_CHANGES.save(deps.storage, id, &files)?;
+    }
+    if let Some(key) = key {
+        KEY_POSTS.save(deps.storage, id, &key)?;
+    }
+    let msgs = remote_dispatch(
+        deps.as_ref(),
+        &app,
+        host_chain,
+        info.funds,
+        transfer,
+        id,
+        jackal_msgs,
+    )?;
+
+    Ok(app
+        .response("send_content")
+        .add_attribute("operation_id", id.to_string())
+        .add_messages(deposit)
+        .add_messages(msgs))
+}
+
+/// Renew the storage plan when the renewal policy says it is due.
+/// Anyone can call this, it is a no-op until then.
+fn tick(deps: DepsMut, env: Env, info: MessageInfo, app: Usb) -> UsbResult {
+    let config = CONFIG.load(deps.storage)?;
+    let (Some(policy), Some(plan)) = (&config.renewal, STORAGE_PLAN.may_load(deps.storage)?) else {
+        return Ok(app.custom_response("tick", vec![("renewal", "none")]));
+    };
+    // a renewal is already on its way, or failed and is left to `RetryOperation` or a new policy,
+    // so no more than one renewal is ever paid for at once
+    if let Some(id) = RENEWAL.may_load(deps.storage)? {
+        let status = match OPERATIONS.load(deps.storage, id)?.status {
+            OperationStatus::Succeeded => None,
+            OperationStatus::Pending => Some("pending"),
+            OperationStatus::Failed { .. } | OperationStatus::TimedOut => Some("failed"),
+        };
+        if let Some(status) = status {
+            return Ok(app.custom_response(
+                "tick",
+                vec![
+                    ("renewal", status.to_string()),
+                    ("operation_id", id.to_string()),
+                ],
+            ));
+        }
+    }
+    let renew_at = plan.expires.minus_days(policy.days_before_expiry);
+    if env.block.time < renew_at {
+        return Ok(app.custom_response("tick", vec![("renewal", "none")]));
+    }
+
+    let bytes = policy.bytes.unwrap_or(plan.bytes);
+    let pricing = config
+        .storage_pricing
+        .as_ref()
+        .ok_or(UsbError::RenewalWithoutPricing {})?;
+    let cost = pricing.cost(bytes, policy.duration_days)?;
+    if cost > policy.max_spend {
+        return Err(UsbError::RenewalOverBudget {
+            cost,
+            max_spend: policy.max_spend,
+        });
+    }
+
+    // an active plan is upgraded in place, an expired one is bought again
+    let renewal = if env.block.time < plan.expires {
+        JackalMsg::UpgradeStorage {
+            for_address: String::new(),
+            duration_days: policy.duration_days,
+            bytes,
+            payment_denom: plan.payment_denom,
+        }
+    } else {
+        JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days: policy.duration_days,
+            bytes,
+            payment_denom: plan.payment_denom,
+        }
+    };
+    // the renewal is the next recorded operation
+    let id = next_operation_id(deps.storage)?;
+    RENEWAL.save(deps.storage, &id)?;
+    send_content(deps, env, info, vec![renewal], app)
+}
+
+/// Send a failed or timed out operation to jackal again
+fn retry_operation(deps: DepsMut, env: Env, info: MessageInfo, app: Usb, id: u64) -> UsbResult {
+    let mut operation = OPERATIONS.load(deps.storage, id)?;
+    assert_authorized(deps.as_ref(), &app, &info.sender, &operation.kinds)?;
+    match operation.status {
+        OperationStatus::Succeeded => return Err(UsbError::OperationSucceeded(id)),
+        OperationStatus::Pending => return Err(UsbError::OperationPending(id)),
+        OperationStatus::Failed { .. } | OperationStatus::TimedOut => {}
+    }
+    sublease::assert_not_cancelled(deps.storage, id)?;
+    if operation.retries >= MAX_RETRIES {
+        return Err(UsbError::RetryLimit {
+            id,
+            max: MAX_RETRIES,
+        });
+    }
+
+    operation.status = OperationStatus::Pending;
+    operation.retries += 1;
+    operation.responses = vec![];
+    operation.updated_height = env.block.height;
+    OPERATIONS.save(deps.storage, id, &operation)?;
+    record_retry(deps.storage, &env, id, &info.funds)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let jackal_msgs = OPERATION_MSGS.load(deps.storage, id)?;
+    let msgs = remote_dispatch(
+        deps.as_ref(),
+        &app,
+        &config.host_chain,
+        info.funds.clone(),
+        info.funds,
+        id,
+        jackal_msgs,
+    )?;
+
+    Ok(app
+        .response("retry_operation")
+        .add_attribute("operation_id", id.to_string())
+        .add_attribute("retries", operation.retries.to_string())
+        .add_messages(msgs))
+}
+
+/// Msgs sending the encoded jackal msgs of operation `id` to the app on the remote account.
+/// The `attached` funds are moved to the account, then `transfer` is sent from the account
+/// to the remote proxy over ics20, before the msgs.
+#[allow(clippy::too_many_arguments)]
+fn remote_dispatch(
+    deps: Deps,
+    app: &Usb,
+    host_chain: &ChainName,
+    attached: Vec<Coin>,
+    transfer: Vec<Coin>,
+    id: u64,
+    jackal_msgs: Vec<CosmosMsg>,
+) -> UsbResult<Vec<CosmosMsg>> {
+    let ibc_client = app.ibc_client(deps);
+    let mut msgs = vec![];
+
+    if !attached.is_empty() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: app.proxy_address(deps)?.into_string(),
+                amount: attached,
             }
+            .into(),
+        );
+    }
+    if !transfer.is_empty() {
+        msgs.push(ibc_client.ics20_transfer(host_chain.to_string(), transfer)?);
+    }
+
+    // the app installed on the remote account broadcasts the msgs, the result comes back in the dispatch callback
+    msgs.push(ibc_client.module_ibc_action(
+        host_chain.to_string(),
+        ModuleInfo::from_id_latest(USB_ID)?,
+        &UsbIbcMsg::Dispatch { msgs: jackal_msgs },
+        Some(CallbackInfo::new(
+            DISPATCH_CALLBACK_ID,
+            Some(to_json_binary(&id)?),
+        )),
+    )?);
+
+    Ok(msgs)
+}
+
+/// Funds to transfer to the remote proxy: the `attached` funds, topped up from the account
+/// balance and the `deposited` funds to pay for the storage bought by `msgs`
+fn storage_funding(
+    deps: Deps,
+    app: &Usb,
+    pricing: &StoragePricing,
+    msgs: &[JackalMsg],
+    attached: &[Coin],
+    deposited: &[Coin],
+) -> UsbResult<Vec<Coin>> {
+    let required = msgs.iter().try_fold(Uint128::zero(), |total, msg| {
+        let cost = match msg {
             JackalMsg::BuyStorage {
-                for_address,
-                duration_days,
                 bytes,
-                payment_denom,
-            } => {
-                let buy_storage_type = String::from("/canine_chain.storage.MsgBuyStorage");
-                let msg_buy_storage = MsgBuyStorage {
-                    creator: info.sender.to_string(),
-                    for_address,
-                    duration_days,
-                    bytes,
-                    payment_denom,
-                };
-                let encoded_buy_storage = msg_buy_storage.encode_to_vec();
-
-                let buy_storage: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: buy_storage_type,
-                    value: cosmwasm_std::Binary(encoded_buy_storage),
-                };
-                Ok(buy_storage)
-            }
-            JackalMsg::UpgradeStorage {
-                for_address,
                 duration_days,
-                bytes,
-                payment_denom,
-            } => {
-                let upgrade_storage_type = String::from("/canine_chain.storage.MsgUpgradeStorage");
-                let msg_upgrade_storage = MsgUpgradeStorage {
-                    creator: info.sender.to_string(),
-                    for_address,
-                    duration_days,
-                    bytes,
-                    payment_denom,
-                };
-                let encoded_upgrade_storage = msg_upgrade_storage.encode_to_vec();
-                let upgrade_storage: CosmosMsg<Empty> = CosmosMsg::Stargate {
-                    type_url: upgrade_storage_type,
-                    value: cosmwasm_std::Binary(encoded_upgrade_storage),
-                };
-                Ok(upgrade_storage)
+                ..
             }
-            JackalMsg::PostKey { key } => {
-                let post_key_type = String::from("/canine_chain.storage.MsgPostKey");
-                let msg_post_key = MsgPostKey {
-                    key,
-                    creator: info.sender.to_string(),
-                };
-
-                let encoded_post_key = msg_post_key.encode_to_vec();
-                let post_key = CosmosMsg::Stargate {
-                    type_url: post_key_type,
-                    value: cosmwasm_std::Binary(encoded_post_key),
-                };
-                Ok(post_key)
-            }
-            _ => Err(UsbError::NotImplemented()),
-        }
-        .map_err(|error| error)?;
-
-        jackal_msgs.push(msg);
-    }
-
-    // sends msg to ibc-client for ibc transfer & execution on jackal
-    let send_as_proxy: CosmosMsg = wasm_execute(
-        app.ibc_client(deps.as_ref()).module_address()?,
-        &ibc_client::ExecuteMsg::RemoteAction {
-            host_chain: ChainName::from_string("jackal".to_string())?.to_string(),
-            action: HostAction::Dispatch {
-                manager_msgs: vec![manager::ExecuteMsg::ExecOnModule {
-                    module_id: PROXY.to_string(),
-                    exec_msg: to_json_binary(&proxy::ExecuteMsg::ModuleAction {
-                        msgs: jackal_msgs,
-                    })?,
-                }],
-            },
+            | JackalMsg::UpgradeStorage {
+                bytes,
+                duration_days,
+                ..
+            } => pricing.cost(*bytes, *duration_days)?,
+            _ => Uint128::zero(),
+        };
+        total.checked_add(cost).map_err(StdError::from)
+    })?;
+
+    let denom = &pricing.funding_denom;
+    let in_denom = |coins: &[Coin]| -> Uint128 {
+        coins
+            .iter()
+            .filter(|coin| &coin.denom == denom)
+            .map(|coin| coin.amount)
+            .sum()
+    };
+    let sent = in_denom(attached);
+    if sent >= required {
+        return Ok(attached.to_vec());
+    }
+
+    // the funds sent along and deposited only reach the account in this tx, on top of its balance
+    let balance = deps
+        .querier
+        .query_balance(app.proxy_address(deps)?, denom)?
+        .amount;
+    let available = balance + sent + in_denom(deposited);
+    if available < required {
+        return Err(UsbError::InsufficientFunds {
+            denom: denom.clone(),
+            required,
+            available,
+        });
+    }
+
+    let mut funds: Vec<Coin> = attached
+        .iter()
+        .filter(|coin| &coin.denom != denom)
+        .cloned()
+        .collect();
+    funds.push(Coin::new(required.u128(), denom));
+    Ok(funds)
+}
+
+/// Record a new operation for the batch of encoded `msgs` of `kinds`
+fn start_operation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    kinds: Vec<JackalMsgKind>,
+    msgs: &Vec<CosmosMsg>,
+    status: OperationStatus,
+) -> UsbResult<u64> {
+    let id = next_operation_id(storage)?;
+    NEXT_OPERATION_ID.save(storage, &(id + 1))?;
+    OPERATION_MSGS.save(storage, id, msgs)?;
+    OPERATIONS.save(
+        storage,
+        id,
+        &Operation {
+            id,
+            kinds,
+            status,
+            responses: vec![],
+            retries: 0,
+            created_height: env.block.height,
+            updated_height: env.block.height,
         },
-        info.funds,
-    )?
-    .into();
-
-    // execute as account, with reply on success
-    let msg = executor.execute_with_reply_and_data(
-        send_as_proxy,
-        cosmwasm_std::ReplyOn::Success,
-        JACKAL_MSG_REPLY_ID,
     )?;
+    Ok(id)
+}
+
+/// Id the next dispatched batch is recorded with
+pub(crate) fn next_operation_id(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(NEXT_OPERATION_ID.may_load(storage)?.unwrap_or_default())
+}
+
+/// Broadcast `msgs` with the proxy of the account.
+/// Each msg is sent on its own, so the reply can pick up its response.
+pub(crate) fn broadcast(deps: Deps, app: &Usb, msgs: Vec<CosmosMsg>) -> UsbResult<Vec<SubMsg>> {
+    let executor = app.executor(deps);
+    msgs.into_iter()
+        .map(|msg| {
+            executor
+                .execute_with_reply_and_data(msg, ReplyOn::Success, JACKAL_MSG_REPLY_ID)
+                .map_err(Into::into)
+        })
+        .collect()
+}
+
+/// Whether the app runs on the configured jackal chain, in which case msgs are sent without ibc
+pub(crate) fn is_local(env: &Env, config: &Config) -> bool {
+    ChainName::new(env) == config.host_chain
+}
 
-    Ok(app.response("send_content").add_submessage(msg))
+/// Load the address of the accounts proxy on the configured host chain.
+/// The address is looked up via the ibc-client once and cached afterwards.
+pub(crate) fn remote_proxy(deps: DepsMut, app: &Usb, config: &Config) -> UsbResult<String> {
+    let host_chain = &config.host_chain;
+    let remote_proxy = match REMOTE_PROXIES.may_load(deps.storage, host_chain)? {
+        Some(remote_proxy) => remote_proxy,
+        None => {
+            let remote_proxy = app
+                .ibc_client(deps.as_ref())
+                .remote_proxy_addr(host_chain.as_str())?
+                .ok_or_else(|| UsbError::RemoteProxyNotFound(host_chain.to_string()))?;
+            REMOTE_PROXIES.save(deps.storage, host_chain, &remote_proxy)?;
+            remote_proxy
+        }
+    };
+
+    // a mismatch means the host chain isn't the jackal network the config describes
+    if !remote_proxy.starts_with(&format!("{}1", config.bech32_prefix)) {
+        return Err(UsbError::RemoteProxyPrefix {
+            address: remote_proxy,
+            prefix: config.bech32_prefix.clone(),
+        });
+    }
+
+    Ok(remote_proxy)
+}
+
+/// Fill in the configured payment denom, and the account as beneficiary, of storage purchases that leave them empty,
+/// and the account as owner of deletes and sharing or ownership changes that leave it empty.
+/// Deletes take the hashed account, the others the owner address the file is stored under.
+fn with_defaults(msg: JackalMsg, denom: &str, creator: &str) -> JackalMsg {
+    let fill = |value: String, default: &str| {
+        if value.is_empty() {
+            default.to_string()
+        } else {
+            value
+        }
+    };
+    match msg {
+        JackalMsg::BuyStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } => JackalMsg::BuyStorage {
+            for_address: fill(for_address, creator),
+            duration_days,
+            bytes,
+            payment_denom: fill(payment_denom, denom),
+        },
+        JackalMsg::UpgradeStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } => JackalMsg::UpgradeStorage {
+            for_address: fill(for_address, creator),
+            duration_days,
+            bytes,
+            payment_denom: fill(payment_denom, denom),
+        },
+        JackalMsg::Delete { path, account } => JackalMsg::Delete {
+            path,
+            account: fill(account, &hash_and_hex(creator)),
+        },
+        JackalMsg::DeleteFile { hash_path, account } => JackalMsg::DeleteFile {
+            hash_path,
+            account: fill(account, &hash_and_hex(creator)),
+        },
+        JackalMsg::AddViewers {
+            viewers,
+            tracking_number,
+            address,
+            owner,
+        } => JackalMsg::AddViewers {
+            viewers,
+            tracking_number,
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::DeleteViewers {
+            viewers,
+            tracking_number,
+            address,
+            owner,
+        } => JackalMsg::DeleteViewers {
+            viewers,
+            tracking_number,
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::AddEditors {
+            editors,
+            tracking_number,
+            address,
+            owner,
+        } => JackalMsg::AddEditors {
+            editors,
+            tracking_number,
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::RemoveEditors {
+            editors,
+            tracking_number,
+            address,
+            owner,
+        } => JackalMsg::RemoveEditors {
+            editors,
+            tracking_number,
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::ResetEditors { address, owner } => JackalMsg::ResetEditors {
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::ResetViewers { address, owner } => JackalMsg::ResetViewers {
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+        },
+        JackalMsg::ChangeOwner {
+            address,
+            owner,
+            new_owner,
+        } => JackalMsg::ChangeOwner {
+            owner: fill(owner, &owner_address(&address, creator)),
+            address,
+            new_owner,
+        },
+        msg => msg,
+    }
+}
+
+/// Last storage purchase of `msgs` for the account itself, which becomes its plan
+fn storage_purchase(msgs: &[JackalMsg], creator: &str) -> Option<StoragePurchase> {
+    msgs.iter().rev().find_map(|msg| match msg {
+        JackalMsg::BuyStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        }
+        | JackalMsg::UpgradeStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } if for_address == creator => Some(StoragePurchase {
+            bytes: *bytes,
+            duration_days: *duration_days,
+            payment_denom: payment_denom.clone(),
+        }),
+        _ => None,
+    })
 }
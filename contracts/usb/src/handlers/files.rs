@@ -0,0 +1,409 @@
+//! # files
+//!
+//! Index of the accounts file tree, mirrored from the `PostFile`, `Delete` and `ChangeOwner` msgs of succeeded operations.
+//! Jackal only keeps merkle hashes of the paths, the index keeps them in plain text,
+//! which lets the app make folders and move or copy files by posting them again under their new parent.
+
+use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+use usb::{
+    error::JackalError,
+    helpers::{AccessMap, FilePath},
+    versions::ProtocolVersion,
+    JackalMsg, JackalMsgKind,
+};
+
+use crate::{
+    contract::{Usb, UsbResult},
+    handlers::execute::{assert_authorized, send_indexed_content, tracking_number},
+    state::{FileChange, FileEntry, CHILDREN, CONFIG, FILES, FILE_CHANGES, MERKLE_PATHS},
+    UsbError,
+};
+
+/// Post the folder at `path`, and its missing ancestors when `parents` is set
+pub fn make_dir(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    path: FilePath,
+    parents: bool,
+) -> UsbResult {
+    assert_authorized(
+        deps.as_ref(),
+        &app,
+        &info.sender,
+        &[JackalMsgKind::PostFile],
+    )?;
+    if path.is_root() || FILES.has(deps.storage, path.as_str()) {
+        return Err(UsbError::PathExists(path.to_string()));
+    }
+
+    // folders to post, deepest first
+    let mut folders = vec![path];
+    while let Some(parent) = folders.last().and_then(FilePath::parent) {
+        if parent.is_root() || FILES.has(deps.storage, parent.as_str()) {
+            break;
+        }
+        if !parents {
+            return Err(UsbError::MissingParent(parent.to_string()));
+        }
+        folders.push(parent);
+    }
+
+    let (msgs, files) = folders
+        .into_iter()
+        .rev()
+        .map(|folder| {
+            post(FileEntry {
+                tracking_number: tracking_number(&env, &folder),
+                parent: folder.parent().unwrap_or_else(FilePath::root),
+                path: folder,
+                fid: String::new(),
+                size: None,
+                viewers: AccessMap::new(),
+                editors: AccessMap::new(),
+                created_height: env.block.height,
+                updated_height: env.block.height,
+            })
+        })
+        .unzip();
+    send_indexed_content(deps, env, info, msgs, files, app)
+}
+
+/// Post the indexed file or folder at `from`, and everything under it, at `to`.
+/// The entries keep their FID, tracking number, viewers and editors, and are deleted at `from` when `keep` isn't set.
+///
+/// Only indexed entries are moved. On canine-chain v3 a folder is deleted with `MsgDangerDelete`,
+/// which also deletes the entries under it that the index doesn't know of.
+/// v4 has no `MsgDangerDelete`, so every entry is deleted with `MsgDeleteFile` there.
+pub fn relocate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    from: FilePath,
+    to: FilePath,
+    keep: bool,
+) -> UsbResult {
+    let version = CONFIG.load(deps.storage)?.protocol_version;
+    let delete = match version {
+        ProtocolVersion::V3 => JackalMsgKind::Delete,
+        ProtocolVersion::V4 => JackalMsgKind::DeleteFile,
+    };
+    let kinds: &[JackalMsgKind] = if keep {
+        &[JackalMsgKind::PostFile]
+    } else {
+        &[JackalMsgKind::PostFile, delete]
+    };
+    assert_authorized(deps.as_ref(), &app, &info.sender, kinds)?;
+    let entry = indexed(deps.storage, &from)?;
+    if to.starts_with(&from) {
+        return Err(UsbError::IntoItself {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+    if to.is_root() || FILES.has(deps.storage, to.as_str()) {
+        return Err(UsbError::PathExists(to.to_string()));
+    }
+    let parent = to.parent().unwrap_or_else(FilePath::root);
+    if !parent.is_root() && !FILES.has(deps.storage, parent.as_str()) {
+        return Err(UsbError::MissingParent(parent.to_string()));
+    }
+
+    // parents sort before their children, so they are posted first
+    let mut entries = vec![entry];
+    entries.extend(descendants(deps.storage, &from)?);
+    let mut msgs = vec![];
+    let mut files = vec![];
+    for entry in &entries {
+        let path = FilePath::new(format!(
+            "{to}{}",
+            &entry.path.as_str()[from.as_str().len()..]
+        ))
+        .map_err(JackalError::from)?;
+        let (msg, file) = post(FileEntry {
+            parent: path.parent().unwrap_or_else(FilePath::root),
+            path,
+            ..entry.clone()
+        });
+        msgs.push(msg);
+        files.push(file);
+    }
+    if !keep {
+        // children are deleted before their parents
+        msgs.extend(entries.into_iter().rev().map(|entry| match version {
+            ProtocolVersion::V3 => JackalMsg::Delete {
+                path: entry.path,
+                account: String::new(),
+            },
+            ProtocolVersion::V4 => JackalMsg::DeleteFile {
+                hash_path: entry.path.merkle_path(),
+                account: String::new(),
+            },
+        }));
+        files.push(FileChange::Delete { path: from });
+    }
+    send_indexed_content(deps, env, info, msgs, files, app)
+}
+
+/// Add the jackal address `recipient` to the viewers of the indexed file at `path`
+pub fn share_file(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    path: FilePath,
+    recipient: String,
+    wrapped_key: String,
+) -> UsbResult {
+    assert_authorized(
+        deps.as_ref(),
+        &app,
+        &info.sender,
+        &[JackalMsgKind::AddViewers],
+    )?;
+    let entry = indexed(deps.storage, &path)?;
+    let config = CONFIG.load(deps.storage)?;
+    if !recipient.starts_with(&format!("{}1", config.bech32_prefix)) {
+        return Err(UsbError::InvalidJackalAddress(recipient));
+    }
+
+    let msg = JackalMsg::AddViewers {
+        viewers: AccessMap::new().with(&recipient, &wrapped_key),
+        tracking_number: entry.tracking_number,
+        address: path.merkle_path(),
+        owner: String::new(),
+    };
+    let file = FileChange::Share {
+        path,
+        recipient,
+        wrapped_key,
+    };
+    send_indexed_content(deps, env, info, vec![msg], vec![file], app)
+}
+
+/// Remove `recipient` from the viewers of the indexed file at `path`
+pub fn revoke_share(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    path: FilePath,
+    recipient: String,
+) -> UsbResult {
+    assert_authorized(
+        deps.as_ref(),
+        &app,
+        &info.sender,
+        &[JackalMsgKind::DeleteViewers],
+    )?;
+    let entry = indexed(deps.storage, &path)?;
+    if !entry.viewers.0.contains_key(&recipient) {
+        return Err(UsbError::NotShared {
+            path: path.to_string(),
+            recipient,
+        });
+    }
+
+    let msg = JackalMsg::DeleteViewers {
+        viewers: vec![recipient.clone()],
+        tracking_number: entry.tracking_number,
+        address: path.merkle_path(),
+        owner: String::new(),
+    };
+    let file = FileChange::Revoke { path, recipient };
+    send_indexed_content(deps, env, info, vec![msg], vec![file], app)
+}
+
+fn indexed(storage: &dyn Storage, path: &FilePath) -> UsbResult<FileEntry> {
+    FILES
+        .may_load(storage, path.as_str())?
+        .ok_or_else(|| UsbError::PathNotIndexed(path.to_string()))
+}
+
+/// `PostFile` msg of `entry`, and its change to the file index
+fn post(entry: FileEntry) -> (JackalMsg, FileChange) {
+    (
+        JackalMsg::PostFile {
+            path: entry.path.clone(),
+            contents: entry.fid.clone(),
+            viewers: entry.viewers.clone(),
+            editors: entry.editors.clone(),
+            tracking_number: entry.tracking_number.clone(),
+        },
+        FileChange::Post {
+            path: entry.path,
+            fid: entry.fid,
+            tracking_number: entry.tracking_number,
+            size: entry.size,
+            viewers: entry.viewers,
+            editors: entry.editors,
+        },
+    )
+}
+
+/// Changes to the file index made by `msgs`, the size of posted files is unknown
+pub(crate) fn file_changes(msgs: &[JackalMsg]) -> Vec<FileChange> {
+    msgs.iter()
+        .filter_map(|msg| match msg {
+            JackalMsg::PostFile {
+                path,
+                contents,
+                viewers,
+                editors,
+                tracking_number,
+            } => Some(FileChange::Post {
+                path: path.clone(),
+                fid: contents.clone(),
+                tracking_number: tracking_number.clone(),
+                size: None,
+                viewers: viewers.clone(),
+                editors: editors.clone(),
+            }),
+            JackalMsg::Delete { path, .. } => Some(FileChange::Delete { path: path.clone() }),
+            JackalMsg::ChangeOwner { address, .. } => Some(FileChange::Disown {
+                address: address.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Apply the file changes of operation `id`, once it succeeded
+pub(crate) fn index_operation(storage: &mut dyn Storage, id: u64, height: u64) -> StdResult<()> {
+    if let Some(changes) = FILE_CHANGES.may_load(storage, id)? {
+        apply_file_changes(storage, changes, height)?;
+        FILE_CHANGES.remove(storage, id);
+    }
+    Ok(())
+}
+
+pub(crate) fn apply_file_changes(
+    storage: &mut dyn Storage,
+    changes: Vec<FileChange>,
+    height: u64,
+) -> StdResult<()> {
+    for change in changes {
+        match change {
+            FileChange::Post {
+                path,
+                fid,
+                tracking_number,
+                size,
+                viewers,
+                editors,
+            } => {
+                // the root is created by `MakeRoot`, it is never posted
+                let Some(parent) = path.parent() else {
+                    continue;
+                };
+                let created_height = FILES
+                    .may_load(storage, path.as_str())?
+                    .map_or(height, |entry| entry.created_height);
+                CHILDREN.save(storage, (parent.as_str(), path.child_name()), &Empty {})?;
+                MERKLE_PATHS.save(storage, &path.merkle_path(), &path)?;
+                FILES.save(
+                    storage,
+                    path.as_str(),
+                    &FileEntry {
+                        path: path.clone(),
+                        fid,
+                        tracking_number,
+                        size,
+                        parent,
+                        viewers,
+                        editors,
+                        created_height,
+                        updated_height: height,
+                    },
+                )?;
+            }
+            FileChange::Delete { path } => remove_entries(storage, &path)?,
+            FileChange::Share {
+                path,
+                recipient,
+                wrapped_key,
+            } => update_viewers(storage, &path, height, |viewers| {
+                viewers.insert(recipient, wrapped_key)
+            })?,
+            FileChange::Revoke { path, recipient } => {
+                update_viewers(storage, &path, height, |viewers| {
+                    viewers.0.remove(&recipient);
+                })?
+            }
+            // only files posted through the app are known by their merkle path
+            FileChange::Disown { address } => {
+                if let Some(path) = MERKLE_PATHS.may_load(storage, &address)? {
+                    remove_entries(storage, &path)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove the entry at `path` and everything under it
+fn remove_entries(storage: &mut dyn Storage, path: &FilePath) -> StdResult<()> {
+    let mut removed = vec![path.clone()];
+    removed.extend(
+        descendants(storage, path)?
+            .into_iter()
+            .map(|entry| entry.path),
+    );
+    for path in removed {
+        FILES.remove(storage, path.as_str());
+        MERKLE_PATHS.remove(storage, &path.merkle_path());
+        if let Some(parent) = path.parent() {
+            CHILDREN.remove(storage, (parent.as_str(), path.child_name()));
+        }
+    }
+    Ok(())
+}
+
+/// Update the viewers of the entry at `path`, unless it was deleted since
+fn update_viewers(
+    storage: &mut dyn Storage,
+    path: &FilePath,
+    height: u64,
+    update: impl FnOnce(&mut AccessMap),
+) -> StdResult<()> {
+    if let Some(mut entry) = FILES.may_load(storage, path.as_str())? {
+        update(&mut entry.viewers);
+        entry.updated_height = height;
+        FILES.save(storage, path.as_str(), &entry)?;
+    }
+    Ok(())
+}
+
+/// Indexed entries under `path`, sorted by path
+pub(crate) fn descendants(storage: &dyn Storage, path: &FilePath) -> StdResult<Vec<FileEntry>> {
+    descendants_page(storage, path, None, usize::MAX)
+}
+
+/// Page of the indexed entries under `path`, after the entry at `start_after`
+pub(crate) fn descendants_page(
+    storage: &dyn Storage,
+    path: &FilePath,
+    start_after: Option<&FilePath>,
+    limit: usize,
+) -> StdResult<Vec<FileEntry>> {
+    // every descendant path is prefixed with `path/`, '0' is the character after '/'
+    let start = format!("{path}/");
+    let end = format!("{path}0");
+    let min = match start_after {
+        Some(after) if after.as_str() >= start.as_str() => Bound::exclusive(after.as_str()),
+        _ => Bound::inclusive(start.as_str()),
+    };
+    FILES
+        .range(
+            storage,
+            Some(min),
+            Some(Bound::exclusive(end.as_str())),
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect()
+}
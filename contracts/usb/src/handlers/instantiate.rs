@@ -1,9 +1,17 @@
 use crate::{
     contract::{Usb, UsbResult},
+    handlers::{
+        execute::{
+            validate_authorized, validate_bech32_prefix, validate_denom, validate_renewal,
+            validate_storage_pricing,
+        },
+        validation::validate_msg_limits,
+    },
     msg::UsbInstantiateMsg,
-    state::{Config, CONFIG, COUNT},
+    state::{Config, Stats, CONFIG, STATS},
 };
 
+use abstract_app::objects::chain_name::ChainName;
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
 
 pub fn instantiate_handler(
@@ -11,11 +19,25 @@ pub fn instantiate_handler(
     _env: Env,
     _info: MessageInfo,
     _app: Usb,
-    _msg: UsbInstantiateMsg,
+    msg: UsbInstantiateMsg,
 ) -> UsbResult {
-    let config: Config = Config {};
+    let config: Config = Config {
+        protocol_version: msg.protocol_version,
+        authorized: validate_authorized(deps.as_ref(), msg.authorized)?,
+        // the ibc-client is usually enabled after the app is installed, so the host is checked on update only
+        host_chain: ChainName::from_string(msg.host_chain)?,
+        bech32_prefix: validate_bech32_prefix(msg.bech32_prefix)?,
+        payment_denom: validate_denom(msg.payment_denom)?,
+        storage_pricing: msg
+            .storage_pricing
+            .map(validate_storage_pricing)
+            .transpose()?,
+        renewal: msg.renewal,
+        msg_limits: validate_msg_limits(msg.msg_limits.unwrap_or_default())?,
+    };
+    validate_renewal(&config)?;
 
     CONFIG.save(deps.storage, &config)?;
-    COUNT.save(deps.storage, &0)?;
+    STATS.save(deps.storage, &Stats::default())?;
     Ok(Response::new())
 }
@@ -0,0 +1,101 @@
+//! # keys
+//!
+//! Public key the account posted on jackal, cached once the `PostKey` msg succeeded
+//! and registered in the usb-adapter, where other accounts look it up before sharing files with it.
+
+use abstract_app::{
+    objects::{module::ModuleInfo, AccountId},
+    sdk::{features::AccountIdentification, ModuleInterface, ModuleRegistryInterface},
+    std::adapter::{AdapterRequestMsg, AuthorizedAddressesResponse, BaseQueryMsg},
+};
+use cosmwasm_std::{wasm_execute, Deps, DepsMut, Env, SubMsg};
+use usb::JackalMsg;
+use usb_adapter::{
+    msg::{ExecuteMsg, PublicKeyResponse, QueryMsg, UsbAdapterExecuteMsg, UsbAdapterQueryMsg},
+    USB_ID as ADAPTER_ID,
+};
+
+use crate::{
+    contract::{Usb, UsbResult},
+    replies::KEY_REGISTRATION_REPLY_ID,
+    state::{KEY_POSTS, PUBLIC_KEY},
+};
+
+/// Last public key posted by `msgs`
+pub(crate) fn posted_key(msgs: &[JackalMsg]) -> Option<String> {
+    msgs.iter().rev().find_map(|msg| match msg {
+        JackalMsg::PostKey { key } => Some(key.clone()),
+        _ => None,
+    })
+}
+
+/// Cache the public key posted by operation `id`, once it succeeded
+pub(crate) fn cache_operation_key(
+    deps: DepsMut,
+    env: &Env,
+    app: &Usb,
+    id: u64,
+) -> UsbResult<Option<SubMsg>> {
+    let Some(key) = KEY_POSTS.may_load(deps.storage, id)? else {
+        return Ok(None);
+    };
+    KEY_POSTS.remove(deps.storage, id);
+    cache_key(deps, env, app, key)
+}
+
+/// Cache `key`, and register it in the usb-adapter when the account installed it and authorized the app.
+/// The registration replies on error, so the adapter can't fail the dispatch or ibc callback that posted the key.
+pub(crate) fn cache_key(
+    deps: DepsMut,
+    env: &Env,
+    app: &Usb,
+    key: String,
+) -> UsbResult<Option<SubMsg>> {
+    PUBLIC_KEY.save(deps.storage, &key)?;
+    let deps = deps.as_ref();
+
+    let Ok(adapter) = app.modules(deps).module_address(ADAPTER_ID) else {
+        return Ok(None);
+    };
+    let proxy_address = app.proxy_address(deps)?.into_string();
+    let authorized: AuthorizedAddressesResponse = deps.querier.query_wasm_smart(
+        &adapter,
+        &QueryMsg::Base(BaseQueryMsg::AuthorizedAddresses {
+            proxy_address: proxy_address.clone(),
+        }),
+    )?;
+    if !authorized.addresses.contains(&env.contract.address) {
+        return Ok(None);
+    }
+    let msg = wasm_execute(
+        adapter,
+        &ExecuteMsg::Module(AdapterRequestMsg::new(
+            Some(proxy_address),
+            UsbAdapterExecuteMsg::RegisterPublicKey { key },
+        )),
+        vec![],
+    )?;
+    Ok(Some(SubMsg::reply_on_error(msg, KEY_REGISTRATION_REPLY_ID)))
+}
+
+/// Public key of the account, or of another account as registered in the usb-adapter
+pub(crate) fn query_public_key(
+    deps: Deps,
+    app: &Usb,
+    account_id: AccountId,
+) -> UsbResult<PublicKeyResponse> {
+    if account_id == app.account_id(deps)? {
+        return Ok(PublicKeyResponse {
+            key: PUBLIC_KEY.may_load(deps.storage)?,
+        });
+    }
+    let adapter = app
+        .module_registry(deps)?
+        .query_module(ModuleInfo::from_id_latest(ADAPTER_ID)?)?
+        .reference
+        .unwrap_adapter()?;
+    Ok(deps.querier.query_wasm_smart(
+        adapter,
+        &QueryMsg::from(UsbAdapterQueryMsg::PublicKey { account_id }),
+    )?)
+}
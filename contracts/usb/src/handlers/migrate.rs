@@ -3,18 +3,55 @@ use crate::{
         Usb, UsbResult
     },
     msg::UsbMigrateMsg,
+    state::{LastDispatch, Operation, Stats, OPERATIONS, STATS},
 };
 
 use abstract_app::traits::AbstractResponse;
-use cosmwasm_std::{DepsMut, Env};
+use cosmwasm_std::{DepsMut, Env, Order, StdResult, Storage};
+use cw_storage_plus::Item;
+
+/// Counter of the app template, replaced by the stats
+const COUNT: Item<i32> = Item::new("count");
 
 /// Handle the app migrate msg
 /// The top-level Abstract app does version checking and dispatches to this handler
 pub fn migrate_handler(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     app: Usb,
     _msg: UsbMigrateMsg,
 ) -> UsbResult {
+    COUNT.remove(deps.storage);
+    if !STATS.exists(deps.storage) {
+        let stats = recorded_stats(deps.storage)?;
+        STATS.save(deps.storage, &stats)?;
+    }
     Ok(app.response("migrate"))
 }
+
+/// Stats of the operations recorded before the app kept them.
+/// Bytes purchased and funds sent weren't recorded, they are counted from the migration on.
+fn recorded_stats(storage: &dyn Storage) -> StdResult<Stats> {
+    let mut stats = Stats::default();
+    for operation in OPERATIONS.range(storage, None, None, Order::Ascending) {
+        let (
+            id,
+            Operation {
+                kinds,
+                status,
+                created_height,
+                ..
+            },
+        ) = operation?;
+        stats.batches += 1;
+        for kind in kinds {
+            stats.count_msg(kind);
+        }
+        stats.last_dispatch = Some(LastDispatch {
+            operation_id: id,
+            height: created_height,
+            status,
+        });
+    }
+    Ok(stats)
+}
@@ -1,7 +1,13 @@
 pub mod execute;
+pub mod files;
 pub mod instantiate;
+pub mod keys;
 pub mod migrate;
 pub mod query;
+pub mod remote_queries;
+pub mod stats;
+pub mod sublease;
+pub mod validation;
 
 pub use self::{
     execute::execute_handler, instantiate::instantiate_handler, migrate::migrate_handler,
@@ -2,31 +2,249 @@ use crate::{
     contract::{
         Usb, UsbResult
     },
-    msg::{ConfigResponse, CountResponse, UsbQueryMsg},
-    state::{CONFIG, COUNT},
+    handlers::{
+        files::descendants_page,
+        keys::query_public_key,
+        sublease::{lease_view, leases_page},
+    },
+    msg::{
+        ConfigResponse, LeasesResponse, ListDirResponse, ListingsResponse, OperationsResponse,
+        RemoteQueriesResponse, Share, SharesResponse, StoragePlanResponse, TreeResponse,
+        UsbQueryMsg,
+    },
+    state::{
+        FileEntry, Lease, Listing, Operation, RemoteQuery, Stats, CHILDREN, CONFIG, FILES,
+        LATEST_ANSWERS, LEASES, LISTINGS, OPERATIONS, REMOTE_QUERIES, STATS, STORAGE_PLAN,
+    },
 };
 
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{to_json_binary, to_json_string, Binary, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+use usb::{
+    helpers::{access_id, Access, FilePath},
+    JackalQuery,
+};
 
-pub fn query_handler(
-    deps: Deps,
-    _env: Env,
-    _app: &Usb,
-    msg: UsbQueryMsg,
-) -> UsbResult<Binary> {
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn query_handler(deps: Deps, env: Env, app: &Usb, msg: UsbQueryMsg) -> UsbResult<Binary> {
     match msg {
         UsbQueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        UsbQueryMsg::Count {} => to_json_binary(&query_count(deps)?),
+        UsbQueryMsg::Stats {} => to_json_binary(&query_stats(deps)?),
+        UsbQueryMsg::StoragePlan {} => to_json_binary(&query_storage_plan(deps)?),
+        UsbQueryMsg::PublicKey { account_id } => {
+            return Ok(to_json_binary(&query_public_key(deps, app, account_id)?)?)
+        }
+        UsbQueryMsg::Listing { id } => to_json_binary(&query_listing(deps, id)?),
+        UsbQueryMsg::Listings { start_after, limit } => {
+            to_json_binary(&query_listings(deps, start_after, limit)?)
+        }
+        UsbQueryMsg::Lease { id } => to_json_binary(&query_lease(deps, &env, id)?),
+        UsbQueryMsg::Leases {
+            taker,
+            start_after,
+            limit,
+        } => to_json_binary(&query_leases(deps, &env, taker, start_after, limit)?),
+        UsbQueryMsg::File { path } => to_json_binary(&query_file(deps, path)?),
+        UsbQueryMsg::ListDir {
+            path,
+            start_after,
+            limit,
+        } => to_json_binary(&query_list_dir(deps, path, start_after, limit)?),
+        UsbQueryMsg::Shares { path } => to_json_binary(&query_shares(deps, path)?),
+        UsbQueryMsg::Tree {
+            path,
+            start_after,
+            limit,
+        } => to_json_binary(&query_tree(deps, path, start_after, limit)?),
+        UsbQueryMsg::Operation { id } => to_json_binary(&query_operation(deps, id)?),
+        UsbQueryMsg::Operations { start_after, limit } => {
+            to_json_binary(&query_operations(deps, start_after, limit)?)
+        }
+        UsbQueryMsg::RemoteQuery { id } => to_json_binary(&query_remote_query(deps, id)?),
+        UsbQueryMsg::RemoteQueries { start_after, limit } => {
+            to_json_binary(&query_remote_queries(deps, start_after, limit)?)
+        }
+        UsbQueryMsg::LatestAnswer { query } => to_json_binary(&query_latest_answer(deps, query)?),
     }
     .map_err(Into::into)
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let _config = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {})
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        protocol_version: config.protocol_version,
+        authorized: config.authorized,
+        host_chain: config.host_chain.into_string(),
+        bech32_prefix: config.bech32_prefix,
+        payment_denom: config.payment_denom,
+        msg_limits: config.msg_limits,
+        storage_pricing: config.storage_pricing,
+        renewal: config.renewal,
+    })
+}
+
+fn query_stats(deps: Deps) -> StdResult<Stats> {
+    Ok(STATS.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn query_storage_plan(deps: Deps) -> StdResult<StoragePlanResponse> {
+    let plan = STORAGE_PLAN.may_load(deps.storage)?;
+    Ok(StoragePlanResponse { plan })
+}
+
+fn query_listing(deps: Deps, id: u64) -> StdResult<Listing> {
+    LISTINGS.load(deps.storage, id)
+}
+
+fn query_listings(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let listings = LISTINGS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect::<StdResult<_>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn query_lease(deps: Deps, env: &Env, id: u64) -> StdResult<Lease> {
+    Ok(lease_view(env, LEASES.load(deps.storage, id)?))
+}
+
+fn query_leases(
+    deps: Deps,
+    env: &Env,
+    taker: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<LeasesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let leases = leases_page(deps.storage, env, taker.as_deref(), start_after, limit)?;
+    Ok(LeasesResponse { leases })
+}
+
+fn query_file(deps: Deps, path: FilePath) -> StdResult<FileEntry> {
+    FILES.load(deps.storage, path.as_str())
+}
+
+fn query_list_dir(
+    deps: Deps,
+    path: FilePath,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListDirResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let entries = CHILDREN
+        .prefix(path.as_str())
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|name| FILES.load(deps.storage, &format!("{path}/{}", name?)))
+        .collect::<StdResult<_>>()?;
+    Ok(ListDirResponse { entries })
+}
+
+fn query_shares(deps: Deps, path: FilePath) -> StdResult<SharesResponse> {
+    let entry = FILES.load(deps.storage, path.as_str())?;
+    let shares = entry
+        .viewers
+        .0
+        .into_keys()
+        .map(|recipient| Share {
+            viewer_id: access_id(Access::Viewer, &entry.tracking_number, &recipient),
+            recipient,
+        })
+        .collect();
+    Ok(SharesResponse { shares })
+}
+
+fn query_tree(
+    deps: Deps,
+    path: Option<FilePath>,
+    start_after: Option<FilePath>,
+    limit: Option<u32>,
+) -> StdResult<TreeResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let path = path.unwrap_or_else(FilePath::root);
+    // the entry at `path` sorts before its descendants, so it is on the first page
+    let mut entries: Vec<FileEntry> = match start_after {
+        Some(_) => vec![],
+        None => FILES
+            .may_load(deps.storage, path.as_str())?
+            .into_iter()
+            .take(limit)
+            .collect(),
+    };
+    entries.extend(descendants_page(
+        deps.storage,
+        &path,
+        start_after.as_ref(),
+        limit - entries.len(),
+    )?);
+    Ok(TreeResponse { entries })
+}
+
+fn query_operation(deps: Deps, id: u64) -> StdResult<Operation> {
+    OPERATIONS.load(deps.storage, id)
+}
+
+fn query_operations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OperationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let operations = OPERATIONS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, operation)| operation))
+        .collect::<StdResult<_>>()?;
+    Ok(OperationsResponse { operations })
+}
+
+fn query_remote_query(deps: Deps, id: u64) -> StdResult<RemoteQuery> {
+    REMOTE_QUERIES.load(deps.storage, id)
+}
+
+fn query_remote_queries(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RemoteQueriesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let queries = REMOTE_QUERIES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, remote_query)| remote_query))
+        .collect::<StdResult<_>>()?;
+    Ok(RemoteQueriesResponse { queries })
 }
 
-fn query_count(deps: Deps) -> StdResult<CountResponse> {
-    let count = COUNT.load(deps.storage)?;
-    Ok(CountResponse { count })
+fn query_latest_answer(deps: Deps, query: JackalQuery) -> StdResult<RemoteQuery> {
+    let id = LATEST_ANSWERS.load(deps.storage, &to_json_string(&query)?)?;
+    REMOTE_QUERIES.load(deps.storage, id)
 }
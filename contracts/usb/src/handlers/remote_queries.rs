@@ -0,0 +1,124 @@
+use abstract_app::{
+    sdk::{features::AccountIdentification, IbcInterface},
+    std::ibc::CallbackInfo,
+    traits::AbstractResponse,
+};
+use cosmwasm_std::{
+    to_json_binary, to_json_string, to_json_vec, Binary, ContractResult, DepsMut, Env, MessageInfo,
+    Storage, SystemResult,
+};
+use usb::{JackalQuery, JackalQueryResponse};
+
+use crate::{
+    contract::{Usb, UsbResult},
+    handlers::execute::{assert_query_authorized, is_local, remote_proxy},
+    ibc::QUERY_CALLBACK_ID,
+    state::{
+        OperationStatus, RemoteQuery, CONFIG, LATEST_ANSWERS, NEXT_REMOTE_QUERY_ID, REMOTE_QUERIES,
+    },
+};
+
+/// Send a query of jackal over ibc, its result is recorded by the query callback.
+/// An account on jackal itself is answered right away.
+pub fn query_jackal(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    query: JackalQuery,
+) -> UsbResult {
+    assert_query_authorized(deps.as_ref(), &app, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let local = is_local(&env, &config);
+    let account = if local {
+        app.proxy_address(deps.as_ref())?.into_string()
+    } else {
+        remote_proxy(deps.branch(), &app, &config)?
+    };
+    let query = with_defaults(query, &account);
+    let request = query.to_query_request(config.protocol_version)?;
+
+    let id = NEXT_REMOTE_QUERY_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_REMOTE_QUERY_ID.save(deps.storage, &(id + 1))?;
+    REMOTE_QUERIES.save(
+        deps.storage,
+        id,
+        &RemoteQuery {
+            id,
+            query,
+            status: OperationStatus::Pending,
+            response: None,
+            created_height: env.block.height,
+            updated_height: env.block.height,
+        },
+    )?;
+    let response = app
+        .response("query_jackal")
+        .add_attribute("query_id", id.to_string());
+
+    if local {
+        let result = match deps.querier.raw_query(&to_json_vec(&request)?) {
+            SystemResult::Ok(ContractResult::Ok(data)) => Ok(data),
+            SystemResult::Ok(ContractResult::Err(error)) => Err(OperationStatus::Failed { error }),
+            SystemResult::Err(error) => Err(OperationStatus::Failed {
+                error: error.to_string(),
+            }),
+        };
+        settle_remote_query(deps.storage, &env, id, result)?;
+        return Ok(response);
+    }
+
+    let ibc_query = app.ibc_client(deps.as_ref()).ibc_query(
+        config.host_chain.to_string(),
+        request,
+        CallbackInfo::new(QUERY_CALLBACK_ID, Some(to_json_binary(&id)?)),
+    )?;
+    Ok(response.add_message(ibc_query))
+}
+
+/// Record the data jackal answered the remote query `id` with, or the status it ended in otherwise
+pub(crate) fn settle_remote_query(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    result: Result<Binary, OperationStatus>,
+) -> UsbResult<()> {
+    let mut remote_query = REMOTE_QUERIES.load(storage, id)?;
+    match result {
+        Ok(data) => {
+            remote_query.response = Some(JackalQueryResponse::decode(&remote_query.query, &data));
+            remote_query.status = OperationStatus::Succeeded;
+            LATEST_ANSWERS.save(storage, &to_json_string(&remote_query.query)?, &id)?;
+        }
+        Err(status) => remote_query.status = status,
+    }
+    remote_query.updated_height = env.block.height;
+    REMOTE_QUERIES.save(storage, id, &remote_query)?;
+    Ok(())
+}
+
+/// Query the jackal address of the account when the query leaves it empty
+fn with_defaults(query: JackalQuery, account: &str) -> JackalQuery {
+    let fill = |value: String| {
+        if value.is_empty() {
+            account.to_string()
+        } else {
+            value
+        }
+    };
+    match query {
+        JackalQuery::File { path, owner } => JackalQuery::File {
+            path,
+            owner: fill(owner),
+        },
+        JackalQuery::PubKey { address } => JackalQuery::PubKey {
+            address: fill(address),
+        },
+        JackalQuery::StoragePaymentInfo { address } => JackalQuery::StoragePaymentInfo {
+            address: fill(address),
+        },
+        query => query,
+    }
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::{Coin, Env, StdResult, Storage, Uint128};
+use usb::{JackalMsg, JackalMsgKind};
+
+use crate::state::{
+    LastDispatch, OperationStatus, Stats, DISPATCHED_FUNDS, PURCHASED_BYTES, STATS,
+};
+
+/// Count a new batch of `kinds` dispatched as operation `id`, paid with `funds`
+pub(crate) fn record_dispatch(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    kinds: &[JackalMsgKind],
+    funds: &[Coin],
+    status: OperationStatus,
+) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.batches += 1;
+    for kind in kinds {
+        stats.count_msg(*kind);
+    }
+    dispatched(storage, &mut stats, env, id, funds, status)?;
+    STATS.save(storage, &stats)
+}
+
+/// Record operation `id` being sent to jackal again, paid with `funds`
+pub(crate) fn record_retry(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    funds: &[Coin],
+) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    dispatched(
+        storage,
+        &mut stats,
+        env,
+        id,
+        funds,
+        OperationStatus::Pending,
+    )?;
+    STATS.save(storage, &stats)
+}
+
+/// Record the status operation `id` ended in, counting the storage it bought and the funds sent along once it succeeded
+pub(crate) fn record_status(
+    storage: &mut dyn Storage,
+    id: u64,
+    status: &OperationStatus,
+) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    if *status == OperationStatus::Succeeded {
+        if let Some(bytes) = PURCHASED_BYTES.may_load(storage, id)? {
+            stats.bytes_purchased += bytes;
+            PURCHASED_BYTES.remove(storage, id);
+        }
+        if let Some(funds) = DISPATCHED_FUNDS.may_load(storage, id)? {
+            add_coins(&mut stats.funds_sent, &funds);
+            DISPATCHED_FUNDS.remove(storage, id);
+        }
+    }
+    if let Some(last) = stats
+        .last_dispatch
+        .as_mut()
+        .filter(|last| last.operation_id == id)
+    {
+        last.status = status.clone();
+    }
+    STATS.save(storage, &stats)
+}
+
+/// Count storage bought right away, by a local dispatch
+pub(crate) fn record_purchase(storage: &mut dyn Storage, bytes: Uint128) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.bytes_purchased += bytes;
+    STATS.save(storage, &stats)
+}
+
+/// Bytes of storage bought by `msgs`, for any address
+pub(crate) fn purchased_bytes(msgs: &[JackalMsg]) -> Uint128 {
+    msgs.iter()
+        .map(|msg| match msg {
+            JackalMsg::BuyStorage { bytes, .. } | JackalMsg::UpgradeStorage { bytes, .. } => {
+                Uint128::from(bytes.unsigned_abs())
+            }
+            _ => Uint128::zero(),
+        })
+        .sum()
+}
+
+/// Funds of a succeeded dispatch are counted right away, those of a pending one once it succeeds
+fn dispatched(
+    storage: &mut dyn Storage,
+    stats: &mut Stats,
+    env: &Env,
+    id: u64,
+    funds: &[Coin],
+    status: OperationStatus,
+) -> StdResult<()> {
+    if status == OperationStatus::Succeeded {
+        add_coins(&mut stats.funds_sent, funds);
+    } else if !funds.is_empty() {
+        // a retry sends funds again, on top of those of the failed attempts
+        let mut dispatched = DISPATCHED_FUNDS.may_load(storage, id)?.unwrap_or_default();
+        add_coins(&mut dispatched, funds);
+        DISPATCHED_FUNDS.save(storage, id, &dispatched)?;
+    }
+    stats.last_dispatch = Some(LastDispatch {
+        operation_id: id,
+        height: env.block.height,
+        status,
+    });
+    Ok(())
+}
+
+fn add_coins(total: &mut Vec<Coin>, coins: &[Coin]) {
+    for coin in coins {
+        match total.iter_mut().find(|counted| counted.denom == coin.denom) {
+            Some(counted) => counted.amount += coin.amount,
+            None => total.push(coin.clone()),
+        }
+    }
+}
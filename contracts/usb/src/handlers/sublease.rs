@@ -0,0 +1,356 @@
+//! # sublease
+//!
+//! Slices of the accounts storage plan leased to other accounts.
+//! Takers pay the account, which buys the storage on jackal for them.
+
+use abstract_app::{
+    sdk::{AccountAction, Execution},
+    traits::AbstractResponse,
+};
+use cosmwasm_std::{
+    BankMsg, Coin, DepsMut, Env, MessageInfo, Order, StdError, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::Bound;
+use cw_utils::must_pay;
+use usb::JackalMsg;
+
+use crate::{
+    contract::{Usb, UsbResult},
+    handlers::execute::{is_local, next_operation_id, send_deposited_content, validate_denom},
+    state::{
+        Lease, LeaseStatus, Listing, StoragePurchase, ACTIVE_LEASES, CONFIG, LEASED_BYTES, LEASES,
+        LEASE_OPERATIONS, LISTED_BYTES, LISTINGS, NEXT_LEASE_ID, NEXT_LISTING_ID, STORAGE_PLAN,
+    },
+    UsbError,
+};
+
+/// seconds in a day
+const DAY: u64 = 86_400;
+
+/// Offer `bytes` of the storage plan to other accounts
+#[allow(clippy::too_many_arguments)]
+pub fn create_listing(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    bytes: i64,
+    denom: String,
+    price_per_gb_month: Uint128,
+    max_duration_days: i64,
+) -> UsbResult {
+    app.admin.assert_admin(deps.as_ref(), &info.sender)?;
+    let plan = STORAGE_PLAN
+        .may_load(deps.storage)?
+        .ok_or(UsbError::NoStoragePlan {})?;
+    // storage of the plan is listed or leased out once at most
+    let listed = LISTED_BYTES.may_load(deps.storage)?.unwrap_or_default();
+    let subleased = listed + leased_bytes(deps.storage, &env)?;
+    if bytes <= 0 || subleased + bytes > plan.bytes {
+        return Err(UsbError::ListingExceedsPlan {
+            bytes,
+            subleased,
+            plan_bytes: plan.bytes,
+        });
+    }
+    if max_duration_days <= 0 {
+        return Err(UsbError::ListingDuration(max_duration_days));
+    }
+
+    LISTED_BYTES.save(deps.storage, &(listed + bytes))?;
+    let id = NEXT_LISTING_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_LISTING_ID.save(deps.storage, &(id + 1))?;
+    LISTINGS.save(
+        deps.storage,
+        id,
+        &Listing {
+            id,
+            available_bytes: bytes,
+            denom: validate_denom(denom)?,
+            price_per_gb_month,
+            max_duration_days,
+        },
+    )?;
+
+    Ok(app.custom_response("create_listing", vec![("listing_id", id.to_string())]))
+}
+
+pub fn close_listing(deps: DepsMut, info: MessageInfo, app: Usb, id: u64) -> UsbResult {
+    app.admin.assert_admin(deps.as_ref(), &info.sender)?;
+    let listing = LISTINGS.load(deps.storage, id)?;
+    LISTINGS.remove(deps.storage, id);
+    LISTED_BYTES.update(deps.storage, |listed| -> StdResult<_> {
+        Ok(listed - listing.available_bytes)
+    })?;
+
+    Ok(app.custom_response("close_listing", vec![("listing_id", id.to_string())]))
+}
+
+/// Lease storage from a listing, the payment goes to the account and the storage is bought for `for_address`
+#[allow(clippy::too_many_arguments)]
+pub fn take_lease(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    app: Usb,
+    listing_id: u64,
+    bytes: i64,
+    duration_days: i64,
+    for_address: String,
+) -> UsbResult {
+    let config = CONFIG.load(deps.storage)?;
+    if !for_address.starts_with(&format!("{}1", config.bech32_prefix)) {
+        return Err(UsbError::InvalidJackalAddress(for_address));
+    }
+    let mut listing = LISTINGS.load(deps.storage, listing_id)?;
+    if bytes <= 0 || bytes > listing.available_bytes {
+        return Err(UsbError::LeaseUnavailable {
+            listing_id,
+            requested: bytes,
+            available: listing.available_bytes,
+        });
+    }
+    if duration_days <= 0 || duration_days > listing.max_duration_days {
+        return Err(UsbError::LeaseDuration {
+            requested: duration_days,
+            max: listing.max_duration_days,
+        });
+    }
+    let start = env.block.time;
+    let end = start.plus_days(duration_days as u64);
+    // the storage of a lease comes out of the plan of the account
+    if STORAGE_PLAN
+        .may_load(deps.storage)?
+        .map_or(true, |plan| plan.expires < end)
+    {
+        return Err(UsbError::LeaseOutlivesPlan {});
+    }
+
+    // a jackal address with an active lease gets its plan upgraded. canine-chain replaces the plan on upgrade,
+    // so it must keep the storage of the active lease: their bytes combined, until the later of their ends.
+    let active = match ACTIVE_LEASES.may_load(deps.storage, &for_address)? {
+        Some(active) => {
+            let active = LEASES.load(deps.storage, active)?;
+            let plan = active.plan(&config.payment_denom);
+            (active.status == LeaseStatus::Active && start < plan.expires)
+                .then_some((active.id, plan))
+        }
+        None => None,
+    };
+    let mut cost = listing.cost(bytes, duration_days)?;
+    let (upgrades, purchase) = match active {
+        Some((id, active)) => {
+            let remaining_days = (active.expires.seconds() - start.seconds()).div_ceil(DAY) as i64;
+            // storage of the active lease kept past its end is paid by this lease
+            if duration_days > remaining_days {
+                let extension = listing.cost(active.bytes, duration_days - remaining_days)?;
+                cost = cost.checked_add(extension).map_err(StdError::from)?;
+            }
+            let purchase = StoragePurchase {
+                bytes: active.bytes + bytes,
+                duration_days: duration_days.max(remaining_days),
+                payment_denom: config.payment_denom.clone(),
+            };
+            (Some(id), purchase)
+        }
+        None => {
+            let purchase = StoragePurchase {
+                bytes,
+                duration_days,
+                payment_denom: config.payment_denom.clone(),
+            };
+            (None, purchase)
+        }
+    };
+
+    let expected = Coin::new(cost.u128(), listing.denom.clone());
+    let received = must_pay(&info, &listing.denom)?;
+    if received != expected.amount {
+        return Err(UsbError::LeasePayment { expected, received });
+    }
+
+    listing.available_bytes -= bytes;
+    LISTINGS.save(deps.storage, listing_id, &listing)?;
+    LISTED_BYTES.update(deps.storage, |listed| -> StdResult<_> {
+        Ok(listed - bytes)
+    })?;
+    LEASED_BYTES.update(deps.storage, end.seconds(), |leased| -> StdResult<_> {
+        Ok(leased.unwrap_or_default() + bytes)
+    })?;
+
+    let msg = if upgrades.is_some() {
+        JackalMsg::UpgradeStorage {
+            for_address: for_address.clone(),
+            duration_days: purchase.duration_days,
+            bytes: purchase.bytes,
+            payment_denom: String::new(),
+        }
+    } else {
+        JackalMsg::BuyStorage {
+            for_address: for_address.clone(),
+            duration_days,
+            bytes,
+            payment_denom: String::new(),
+        }
+    };
+
+    let id = NEXT_LEASE_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_LEASE_ID.save(deps.storage, &(id + 1))?;
+    let operation_id = next_operation_id(deps.storage)?;
+    LEASES.save(
+        deps.storage,
+        id,
+        &Lease {
+            id,
+            listing_id,
+            taker: info.sender.clone(),
+            for_address,
+            bytes,
+            start,
+            end,
+            paid: expected,
+            operation_id,
+            upgrades,
+            plan: Some(purchase.plan(start)),
+            status: LeaseStatus::Pending,
+        },
+    )?;
+    LEASE_OPERATIONS.save(deps.storage, operation_id, &id)?;
+    // the batch is atomic when the account is on jackal, so the lease is active right away
+    if is_local(&env, &config) {
+        settle_lease(deps.storage, id, true)?;
+    }
+
+    // the payment is kept by the account, the purchase is paid like any other of the account
+    let deposit = info.funds.clone();
+    let info = MessageInfo {
+        funds: vec![],
+        ..info
+    };
+    let response = send_deposited_content(deps, env, info, deposit, vec![msg], app)?;
+    Ok(response.add_attribute("lease_id", id.to_string()))
+}
+
+/// Cancel a lease whose storage purchase failed, refunding the taker from the account
+pub fn cancel_lease(deps: DepsMut, info: MessageInfo, app: Usb, id: u64) -> UsbResult {
+    let mut lease = LEASES.load(deps.storage, id)?;
+    if info.sender != lease.taker && !app.admin.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(UsbError::Unauthorized {
+            sender: info.sender.to_string(),
+        });
+    }
+    if lease.status != LeaseStatus::Failed {
+        return Err(UsbError::LeaseNotCancellable(id));
+    }
+
+    // the operation is kept, so its purchase can't be retried once refunded
+    lease.status = LeaseStatus::Cancelled;
+    LEASES.save(deps.storage, id, &lease)?;
+    let leased = LEASED_BYTES.load(deps.storage, lease.end.seconds())? - lease.bytes;
+    if leased == 0 {
+        LEASED_BYTES.remove(deps.storage, lease.end.seconds());
+    } else {
+        LEASED_BYTES.save(deps.storage, lease.end.seconds(), &leased)?;
+    }
+    // the bytes go back to their listing, if it is still open
+    if let Some(mut listing) = LISTINGS.may_load(deps.storage, lease.listing_id)? {
+        listing.available_bytes += lease.bytes;
+        LISTINGS.save(deps.storage, lease.listing_id, &listing)?;
+        LISTED_BYTES.update(deps.storage, |listed| -> StdResult<_> {
+            Ok(listed + lease.bytes)
+        })?;
+    }
+
+    let refund = app
+        .executor(deps.as_ref())
+        .execute(vec![AccountAction::from_vec(vec![BankMsg::Send {
+            to_address: lease.taker.into_string(),
+            amount: vec![lease.paid],
+        }])])?;
+
+    Ok(app
+        .custom_response("cancel_lease", vec![("lease_id", id.to_string())])
+        .add_message(refund))
+}
+
+/// Bytes of the leases that haven't ended yet
+fn leased_bytes(storage: &dyn Storage, env: &Env) -> StdResult<i64> {
+    LEASED_BYTES
+        .range(
+            storage,
+            Some(Bound::exclusive(env.block.time.seconds())),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| item.map(|(_, bytes)| bytes))
+        .sum()
+}
+
+/// Reject retries of an operation whose lease was cancelled, the taker already got its payment back
+pub(crate) fn assert_not_cancelled(storage: &dyn Storage, operation_id: u64) -> UsbResult<()> {
+    let Some(lease) = LEASE_OPERATIONS.may_load(storage, operation_id)? else {
+        return Ok(());
+    };
+    if LEASES.load(storage, lease)?.status == LeaseStatus::Cancelled {
+        return Err(UsbError::LeaseCancelled {
+            id: operation_id,
+            lease,
+        });
+    }
+    Ok(())
+}
+
+/// Update the lease bought by an operation with its outcome
+pub(crate) fn settle_lease(storage: &mut dyn Storage, id: u64, succeeded: bool) -> StdResult<()> {
+    let mut lease = LEASES.load(storage, id)?;
+    if !succeeded {
+        lease.status = LeaseStatus::Failed;
+        return LEASES.save(storage, id, &lease);
+    }
+
+    lease.status = LeaseStatus::Active;
+    // runs in the ibc callback, so a missing upgraded lease is skipped rather than failing it
+    if let Some(mut upgraded) = lease
+        .upgrades
+        .map(|upgraded| LEASES.may_load(storage, upgraded))
+        .transpose()?
+        .flatten()
+    {
+        upgraded.status = LeaseStatus::Upgraded;
+        LEASES.save(storage, upgraded.id, &upgraded)?;
+    }
+    ACTIVE_LEASES.save(storage, &lease.for_address, &id)?;
+    LEASES.save(storage, id, &lease)
+}
+
+/// Leases as reported by queries, with active leases past their end expired
+pub(crate) fn lease_view(env: &Env, mut lease: Lease) -> Lease {
+    if lease.status == LeaseStatus::Active && env.block.time >= lease.end {
+        lease.status = LeaseStatus::Expired;
+    }
+    lease
+}
+
+pub(crate) fn leases_page(
+    storage: &dyn Storage,
+    env: &Env,
+    taker: Option<&str>,
+    start_after: Option<u64>,
+    limit: usize,
+) -> StdResult<Vec<Lease>> {
+    LEASES
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, lease)| {
+                taker.map_or(true, |taker| lease.taker == taker)
+            })
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, lease)| lease_view(env, lease)))
+        .collect()
+}
@@ -0,0 +1,152 @@
+use usb::JackalMsg;
+
+use crate::{
+    contract::UsbResult,
+    error::UsbError,
+    handlers::execute::validate_denom,
+    state::{Config, MsgLimits},
+};
+
+/// length of the hex encoded sha256 hashes jackal addresses files and owners with
+const HASH_LEN: usize = 64;
+
+/// Check every msg of a batch against the rules of canine-chain and the configured limits,
+/// so it fails here instead of on jackal. Expects the defaults to be filled in already.
+pub(crate) fn validate_msgs(config: &Config, msgs: &[JackalMsg]) -> UsbResult<()> {
+    msgs.iter()
+        .enumerate()
+        .try_for_each(|(index, msg)| validate_msg(config, index, msg))
+}
+
+fn validate_msg(config: &Config, index: usize, msg: &JackalMsg) -> UsbResult<()> {
+    match msg {
+        JackalMsg::BuyStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        }
+        | JackalMsg::UpgradeStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } => {
+            let limits = &config.msg_limits;
+            if *bytes < limits.min_bytes || limits.max_bytes.is_some_and(|max| *bytes > max) {
+                return Err(UsbError::StorageBytes {
+                    index,
+                    bytes: *bytes,
+                    min: limits.min_bytes,
+                    max: limits.max_bytes,
+                });
+            }
+            if *duration_days < limits.min_duration_days
+                || limits
+                    .max_duration_days
+                    .is_some_and(|max| *duration_days > max)
+            {
+                return Err(UsbError::StorageDuration {
+                    index,
+                    duration_days: *duration_days,
+                    min: limits.min_duration_days,
+                    max: limits.max_duration_days,
+                });
+            }
+            if *payment_denom != config.payment_denom
+                && !limits.payment_denoms.contains(payment_denom)
+            {
+                return Err(UsbError::UnknownPaymentDenom {
+                    index,
+                    denom: payment_denom.clone(),
+                });
+            }
+            if !for_address.starts_with(&format!("{}1", config.bech32_prefix)) {
+                return Err(UsbError::InvalidStorageAddress {
+                    index,
+                    address: for_address.clone(),
+                });
+            }
+        }
+        JackalMsg::CancelContract { cid } | JackalMsg::SignContract { cid, .. } => {
+            if cid.is_empty() {
+                return Err(UsbError::EmptyCid { index });
+            }
+        }
+        JackalMsg::PostFile { path, .. } => {
+            if path.hash_parent_child().is_err() {
+                return Err(UsbError::InvalidHashParent {
+                    index,
+                    path: path.to_string(),
+                });
+            }
+        }
+        JackalMsg::Delete { account, .. } => validate_hash(index, "account", account)?,
+        JackalMsg::DeleteFile { hash_path, account } => {
+            validate_hash(index, "hash_path", hash_path)?;
+            validate_hash(index, "account", account)?;
+        }
+        JackalMsg::AddViewers { address, owner, .. }
+        | JackalMsg::DeleteViewers { address, owner, .. }
+        | JackalMsg::AddEditors { address, owner, .. }
+        | JackalMsg::RemoveEditors { address, owner, .. }
+        | JackalMsg::ResetEditors { address, owner }
+        | JackalMsg::ResetViewers { address, owner }
+        | JackalMsg::ChangeOwner { address, owner, .. } => {
+            validate_hash(index, "address", address)?;
+            validate_hash(index, "owner", owner)?;
+        }
+        // the usb-adapter registers posted keys under the same rule
+        JackalMsg::PostKey { key } => {
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(UsbError::InvalidPublicKey {
+                    index,
+                    key: key.clone(),
+                });
+            }
+        }
+        JackalMsg::MakeRoot { .. } | JackalMsg::ProvisionFileTree { .. } => {}
+    }
+    Ok(())
+}
+
+/// merkle paths and owners are Hex[ sha256(..) ]
+fn validate_hash(index: usize, field: &str, value: &str) -> UsbResult<()> {
+    if value.len() != HASH_LEN || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(UsbError::InvalidHash {
+            index,
+            field: field.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Limits must leave a non-empty range of storage purchases, paid with valid denoms.
+/// They only tighten the rules of canine-chain, so the minimums can't go below its floor.
+pub(crate) fn validate_msg_limits(limits: MsgLimits) -> UsbResult<MsgLimits> {
+    let floor = MsgLimits::default();
+    if limits.min_bytes < floor.min_bytes || limits.min_duration_days < floor.min_duration_days {
+        return Err(UsbError::InvalidMsgLimits(format!(
+            "canine-chain doesn't sell storage of less than {} bytes or {} days",
+            floor.min_bytes, floor.min_duration_days
+        )));
+    }
+    if limits.max_bytes.is_some_and(|max| max < limits.min_bytes)
+        || limits
+            .max_duration_days
+            .is_some_and(|max| max < limits.min_duration_days)
+    {
+        return Err(UsbError::InvalidMsgLimits(
+            "maximum below the minimum".to_string(),
+        ));
+    }
+    Ok(MsgLimits {
+        payment_denoms: limits
+            .payment_denoms
+            .into_iter()
+            .map(validate_denom)
+            .collect::<UsbResult<_>>()?,
+        ..limits
+    })
+}
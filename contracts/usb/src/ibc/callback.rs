@@ -0,0 +1,148 @@
+use abstract_app::{
+    std::ibc::{CallbackResult, IbcResponseMsg},
+    traits::AbstractResponse,
+};
+use cosmwasm_std::{from_json, Binary, DepsMut, Env, MessageInfo, StdError, StdResult};
+use polytone::callbacks::{ErrorResponse, ExecutionResponse};
+use usb::{JackalMsgKind, JackalMsgResponse};
+
+use crate::{
+    contract::{Usb, UsbResult},
+    handlers::{
+        files::index_operation, keys::cache_operation_key, remote_queries::settle_remote_query,
+        stats::record_status, sublease::settle_lease,
+    },
+    replies::MSG_RESPONSE_ATTRIBUTE,
+    state::{OperationStatus, LEASE_OPERATIONS, OPERATIONS, STORAGE_PLAN, STORAGE_PURCHASES},
+};
+
+/// Error of the callbacks polytone sends on a packet timeout
+const TIMEOUT_ERROR: &str = "timeout";
+
+/// Whether `result` is the callback the polytone note sends when the packet timed out.
+/// Polytone has no dedicated variant for it, but the callback has a fixed shape: an execution error,
+/// or an error of the first query, reading `timeout`. Failures on jackal read `codespace: .., code: ..`.
+fn timed_out(result: &CallbackResult) -> bool {
+    match result {
+        CallbackResult::Execute {
+            result: Err(error), ..
+        } => error == TIMEOUT_ERROR,
+        CallbackResult::Query {
+            result:
+                Err(ErrorResponse {
+                    message_index,
+                    error,
+                }),
+            ..
+        } => message_index.is_zero() && error == TIMEOUT_ERROR,
+        _ => false,
+    }
+}
+
+/// Update the operation of a batch dispatched over ibc with its result on jackal
+pub fn dispatch_callback(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    app: Usb,
+    msg: IbcResponseMsg,
+) -> UsbResult {
+    let id: u64 = from_json(
+        msg.msg
+            .ok_or_else(|| StdError::generic_err("dispatch callback without operation id"))?,
+    )?;
+    let mut operation = OPERATIONS.load(deps.storage, id)?;
+    let mut register_key = None;
+
+    let timed_out = timed_out(&msg.result);
+    operation.status = match msg.result {
+        CallbackResult::Execute {
+            result: Ok(response),
+            ..
+        } => {
+            operation.responses = msg_responses(&operation.kinds, &response)?;
+            if let Some(purchase) = STORAGE_PURCHASES.may_load(deps.storage, id)? {
+                STORAGE_PLAN.save(deps.storage, &purchase.plan(env.block.time))?;
+                STORAGE_PURCHASES.remove(deps.storage, id);
+            }
+            index_operation(deps.storage, id, env.block.height)?;
+            register_key = cache_operation_key(deps.branch(), &env, &app, id)?;
+            OperationStatus::Succeeded
+        }
+        CallbackResult::Execute { .. } if timed_out => OperationStatus::TimedOut,
+        CallbackResult::Execute {
+            result: Err(error), ..
+        }
+        | CallbackResult::FatalError(error) => OperationStatus::Failed { error },
+        CallbackResult::Query { .. } => {
+            return Err(StdError::generic_err("dispatch callback with a query result").into())
+        }
+    };
+    if let Some(lease) = LEASE_OPERATIONS.may_load(deps.storage, id)? {
+        settle_lease(
+            deps.storage,
+            lease,
+            operation.status == OperationStatus::Succeeded,
+        )?;
+    }
+    record_status(deps.storage, id, &operation.status)?;
+    operation.updated_height = env.block.height;
+    OPERATIONS.save(deps.storage, id, &operation)?;
+
+    Ok(app
+        .custom_response("dispatch_callback", vec![("operation_id", id.to_string())])
+        .add_submessages(register_key))
+}
+
+/// Record the answer of jackal to a query sent over ibc
+pub fn query_callback(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    app: Usb,
+    msg: IbcResponseMsg,
+) -> UsbResult {
+    let id: u64 = from_json(
+        msg.msg
+            .ok_or_else(|| StdError::generic_err("query callback without query id"))?,
+    )?;
+    let timed_out = timed_out(&msg.result);
+    let result = match msg.result {
+        // one query is sent at a time
+        CallbackResult::Query {
+            result: Ok(responses),
+            ..
+        } => Ok(responses.into_iter().next().unwrap_or_default()),
+        CallbackResult::Query { .. } if timed_out => Err(OperationStatus::TimedOut),
+        CallbackResult::Query {
+            result: Err(error), ..
+        } => Err(OperationStatus::Failed { error: error.error }),
+        CallbackResult::FatalError(error) => Err(OperationStatus::Failed { error }),
+        CallbackResult::Execute { .. } => {
+            return Err(StdError::generic_err("query callback with an execution result").into())
+        }
+    };
+    settle_remote_query(deps.storage, &env, id, result)?;
+
+    Ok(app.custom_response("query_callback", vec![("query_id", id.to_string())]))
+}
+
+/// Decode the msg responses the app on the remote account emitted, in the order of `kinds`
+fn msg_responses(
+    kinds: &[JackalMsgKind],
+    response: &ExecutionResponse,
+) -> StdResult<Vec<JackalMsgResponse>> {
+    let data = response
+        .result
+        .iter()
+        .flat_map(|result| &result.events)
+        .flat_map(|event| &event.attributes)
+        .filter(|attribute| attribute.key == MSG_RESPONSE_ATTRIBUTE)
+        .map(|attribute| Binary::from_base64(&attribute.value));
+
+    kinds
+        .iter()
+        .zip(data)
+        .map(|(kind, data)| Ok(JackalMsgResponse::decode(*kind, &data?)))
+        .collect()
+}
@@ -0,0 +1,11 @@
+mod callback;
+mod module;
+
+pub use callback::{dispatch_callback, query_callback};
+pub use module::module_ibc_handler;
+
+/// Callback of a batch of jackal msgs dispatched over ibc
+pub const DISPATCH_CALLBACK_ID: &str = "dispatch";
+
+/// Callback of a query of jackal sent over ibc
+pub const QUERY_CALLBACK_ID: &str = "query";
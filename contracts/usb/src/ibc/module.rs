@@ -0,0 +1,27 @@
+use abstract_app::{std::ibc::ModuleIbcMsg, traits::AbstractResponse};
+use cosmwasm_std::{from_json, DepsMut, Env};
+
+use crate::{
+    contract::{Usb, UsbResult},
+    handlers::execute::broadcast,
+    msg::UsbIbcMsg,
+    UsbError, USB_ID,
+};
+
+/// Handle the msgs the app on the origin account sends to the app on its remote account.
+/// The ibc-host only delivers msgs from modules of the account that owns this remote account.
+pub fn module_ibc_handler(deps: DepsMut, _env: Env, app: Usb, msg: ModuleIbcMsg) -> UsbResult {
+    if msg.source_module.id() != USB_ID {
+        return Err(UsbError::Unauthorized {
+            sender: msg.source_module.to_string(),
+        });
+    }
+
+    match from_json(msg.msg)? {
+        UsbIbcMsg::Dispatch { msgs } => {
+            Ok(app
+                .response("dispatch")
+                .add_submessages(broadcast(deps.as_ref(), &app, msgs)?))
+        }
+    }
+}
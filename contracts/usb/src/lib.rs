@@ -1,6 +1,7 @@
 pub mod contract;
 pub mod error;
 mod handlers;
+mod ibc;
 pub mod msg;
 mod replies;
 pub mod state;
@@ -1,27 +1,144 @@
-use crate::contract::Usb;
+use crate::{
+    contract::Usb,
+    state::{
+        Authorization, FileEntry, Lease, Listing, MsgLimits, Operation, RemoteQuery,
+        RenewalPolicy, Stats, StoragePlan, StoragePricing,
+    },
+};
 
+use abstract_app::objects::AccountId;
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::Addr;
-use usb::JackalMsg;
+use cosmwasm_std::{CosmosMsg, Uint128};
+use usb::{
+    helpers::{AccessMap, FilePath},
+    versions::ProtocolVersion,
+    JackalMsg, JackalQuery,
+};
 
 // This is used for type safety and re-exporting the contract endpoint structs.
 abstract_app::app_msg_types!(Usb, UsbExecuteMsg, UsbQueryMsg);
 
 /// App instantiate message
 #[cosmwasm_schema::cw_serde]
-pub struct UsbInstantiateMsg {}
+pub struct UsbInstantiateMsg {
+    /// canine-chain version of the jackal network msgs are sent to
+    pub protocol_version: ProtocolVersion,
+    /// callers other than the admin allowed to send jackal msgs
+    #[serde(default)]
+    pub authorized: Vec<Authorization>,
+    /// name of the jackal chain as registered on the ibc-client, ex: `jackal`
+    pub host_chain: String,
+    /// bech32 prefix of addresses on the jackal chain, ex: `jkl`
+    pub bech32_prefix: String,
+    /// denom storage is paid with when a msg leaves it empty
+    pub payment_denom: String,
+    /// price of storage, to fund the remote proxy for storage purchases
+    #[serde(default)]
+    pub storage_pricing: Option<StoragePricing>,
+    /// renewal of the storage plan, requires a storage pricing
+    #[serde(default)]
+    pub renewal: Option<RenewalPolicy>,
+    /// limits on the jackal msgs sent, canine-chain's minimums when `None`
+    #[serde(default)]
+    pub msg_limits: Option<MsgLimits>,
+}
 
 /// App execute messages
 #[cosmwasm_schema::cw_serde]
 #[derive(cw_orch::ExecuteFns)]
 #[impl_into(ExecuteMsg)]
 pub enum UsbExecuteMsg {
-    JackalMsgs { msgs: Vec<JackalMsg> },
+    /// Low-level escape hatch - send the msgs to jackal as they are
+    JackalMsgs {
+        msgs: Vec<JackalMsg>,
+    },
+    /// Post a file to the accounts file tree on jackal.
+    /// The merkle hashes, account hash and tracking number are derived by the app.
+    UploadFile {
+        /// full path of the file, ex: `s/home/file.txt`
+        path: FilePath,
+        /// FID of the file on the storage providers
+        fid: String,
+        /// viewers, with the file key encrypted to each of them
+        viewers: AccessMap,
+        /// editors, with the file key encrypted to each of them
+        editors: AccessMap,
+        /// size of the file in bytes, recorded in the file index
+        #[serde(default)]
+        size: Option<u64>,
+    },
+    /// Post the folder at `path`, and its missing parent folders when `parents` is set
+    MakeDir { path: FilePath, parents: bool },
+    /// Move or rename an indexed file or folder, with everything under it, in a single batch.
+    /// Every entry is posted again at `to`, keeping its FID, tracking number and access maps, then deleted at `from`.
+    #[fn_name("move_path")]
+    Move { from: FilePath, to: FilePath },
+    /// Copy an indexed file or folder, with everything under it, in a single batch
+    Copy { from: FilePath, to: FilePath },
+    /// Share an indexed file with the jackal address `recipient`.
+    /// The viewer id is derived from the tracking number of the file.
+    ShareFile {
+        path: FilePath,
+        recipient: String,
+        /// file key, ECIES encrypted to the public key of `recipient`
+        wrapped_key: String,
+    },
+    /// Stop sharing an indexed file with `recipient`
+    RevokeShare { path: FilePath, recipient: String },
+    /// Send a failed or timed out batch to jackal again, ex: once the remote proxy can pay the fees
+    RetryOperation { id: u64 },
+    /// Query jackal over ibc, the answer is recorded once the callback comes back.
+    /// Addresses and owners left empty are filled in with the jackal address of the account.
+    QueryJackal { query: JackalQuery },
+    /// Permissionless - renew the storage plan when the renewal policy says it is due, no-op otherwise
+    Tick {},
+    /// Admin method - offer a slice of the storage plan to other accounts
+    CreateListing {
+        bytes: i64,
+        /// denom takers pay in
+        denom: String,
+        /// price of 1 GB for 30 days, in `denom`
+        price_per_gb_month: Uint128,
+        max_duration_days: i64,
+    },
+    /// Admin method - stop offering a listing, its leases are kept
+    CloseListing { id: u64 },
+    /// Lease storage from a listing, paying its price to the account.
+    /// The storage is bought on jackal for `for_address`, upgrading its active lease if any.
+    TakeLease {
+        listing_id: u64,
+        bytes: i64,
+        duration_days: i64,
+        /// jackal address of the taker
+        for_address: String,
+    },
+    /// Taker or admin - cancel a lease whose storage purchase failed, refunding the taker
+    CancelLease { id: u64 },
+    /// Admin method - update the configuration
+    UpdateConfig {
+        protocol_version: Option<ProtocolVersion>,
+        /// replaces the allowlist of callers
+        authorized: Option<Vec<Authorization>>,
+        /// must be registered on the ibc-client of the account
+        host_chain: Option<String>,
+        bech32_prefix: Option<String>,
+        payment_denom: Option<String>,
+        storage_pricing: Option<StoragePricing>,
+        renewal: Option<RenewalPolicy>,
+        msg_limits: Option<MsgLimits>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct UsbMigrateMsg {}
 
+/// Msgs sent over module ibc to the app installed on the accounts remote account on jackal
+#[cosmwasm_schema::cw_serde]
+pub enum UsbIbcMsg {
+    /// Broadcast the encoded jackal msgs with the proxy of the remote account
+    Dispatch { msgs: Vec<CosmosMsg> },
+}
+
 /// App query messages
 #[cosmwasm_schema::cw_serde]
 #[derive(QueryResponses, cw_orch::QueryFns)]
@@ -29,14 +146,134 @@ pub struct UsbMigrateMsg {}
 pub enum UsbQueryMsg {
     #[returns(ConfigResponse)]
     Config {},
-    #[returns(CountResponse)]
-    Count {},
+    /// Statistics of the batches dispatched by the app
+    #[returns(Stats)]
+    Stats {},
+    /// Active storage plan of the account
+    #[returns(StoragePlanResponse)]
+    StoragePlan {},
+    /// Public key posted by the account, or registered by another account in the usb-adapter
+    #[returns(usb_adapter::msg::PublicKeyResponse)]
+    PublicKey { account_id: AccountId },
+    #[returns(Listing)]
+    Listing { id: u64 },
+    #[returns(ListingsResponse)]
+    Listings {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Lease)]
+    Lease { id: u64 },
+    /// Leases, oldest first, optionally only those of `taker`
+    #[returns(LeasesResponse)]
+    Leases {
+        taker: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Indexed file or folder at `path`
+    #[returns(FileEntry)]
+    File { path: FilePath },
+    /// Indexed files and folders directly inside the folder at `path`, by name
+    #[returns(ListDirResponse)]
+    ListDir {
+        path: FilePath,
+        /// name of the last child of the previous page
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Viewers of the indexed file at `path`, with their viewer id
+    #[returns(SharesResponse)]
+    Shares { path: FilePath },
+    /// Every indexed file and folder under `path`, the whole file tree when `None`
+    #[returns(TreeResponse)]
+    Tree {
+        path: Option<FilePath>,
+        /// path of the last entry of the previous page
+        start_after: Option<FilePath>,
+        limit: Option<u32>,
+    },
+    /// Dispatched batch of jackal msgs
+    #[returns(Operation)]
+    Operation { id: u64 },
+    /// Dispatched batches, oldest first
+    #[returns(OperationsResponse)]
+    Operations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Query of jackal sent with `QueryJackal`, with its answer once it succeeded
+    #[returns(RemoteQuery)]
+    RemoteQuery { id: u64 },
+    /// Queries of jackal, oldest first
+    #[returns(RemoteQueriesResponse)]
+    RemoteQueries {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Latest succeeded query of jackal for `query`, as sent with the defaults filled in
+    #[returns(RemoteQuery)]
+    LatestAnswer { query: JackalQuery },
 }
 
 #[cosmwasm_schema::cw_serde]
-pub struct ConfigResponse {}
+pub struct ConfigResponse {
+    pub protocol_version: ProtocolVersion,
+    pub authorized: Vec<Authorization>,
+    pub host_chain: String,
+    pub bech32_prefix: String,
+    pub payment_denom: String,
+    pub msg_limits: MsgLimits,
+    pub storage_pricing: Option<StoragePricing>,
+    pub renewal: Option<RenewalPolicy>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct StoragePlanResponse {
+    /// `None` until a storage purchase for the account succeeded
+    pub plan: Option<StoragePlan>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LeasesResponse {
+    pub leases: Vec<Lease>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListDirResponse {
+    pub entries: Vec<FileEntry>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TreeResponse {
+    /// entries sorted by path
+    pub entries: Vec<FileEntry>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SharesResponse {
+    pub shares: Vec<Share>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Share {
+    /// jackal address of the viewer
+    pub recipient: String,
+    /// Hex[ hash( "v" + trackingNumber + recipient ) ], key of the viewer in the viewers map on jackal
+    pub viewer_id: String,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct OperationsResponse {
+    pub operations: Vec<Operation>,
+}
 
 #[cosmwasm_schema::cw_serde]
-pub struct CountResponse {
-    pub count: i32,
+pub struct RemoteQueriesResponse {
+    pub queries: Vec<RemoteQuery>,
 }
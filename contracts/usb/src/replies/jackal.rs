@@ -1,8 +1,40 @@
 use abstract_app::sdk::AbstractResponse;
-use cosmwasm_std::{DepsMut, Env, Reply};
+use cosmwasm_std::{Binary, DepsMut, Env, Reply, StdError};
+use cw_utils::parse_execute_response_data;
+use usb::JackalMsgResponse;
 
-use crate::contract::{Usb, UsbResult};
+use crate::{
+    contract::{Usb, UsbResult},
+    replies::MSG_RESPONSE_ATTRIBUTE,
+    state::{DISPATCHING, OPERATIONS},
+};
 
-pub fn jackal_reply(_deps: DepsMut, _env: Env, app: Usb, _reply: Reply) -> UsbResult {
-    Ok(app.response("instantiate_reply"))
+/// Pick up the response of a jackal msg broadcast by the proxy.
+/// The response is emitted as an attribute, for the app that dispatched the msg over ibc,
+/// and recorded in the operation of a local dispatch.
+pub fn jackal_reply(deps: DepsMut, _env: Env, app: Usb, reply: Reply) -> UsbResult {
+    let result = reply.result.into_result().map_err(StdError::generic_err)?;
+    // the proxy forwards the response of the msg as its own data
+    let data = match result.data {
+        Some(data) => parse_execute_response_data(&data)?.data.unwrap_or_default(),
+        None => Binary::default(),
+    };
+
+    if let Some(id) = DISPATCHING.may_load(deps.storage)? {
+        let mut operation = OPERATIONS.load(deps.storage, id)?;
+        if let Some(kind) = operation.kinds.get(operation.responses.len()) {
+            operation
+                .responses
+                .push(JackalMsgResponse::decode(*kind, &data));
+        }
+        if operation.responses.len() == operation.kinds.len() {
+            DISPATCHING.remove(deps.storage);
+        }
+        OPERATIONS.save(deps.storage, id, &operation)?;
+    }
+
+    Ok(app.custom_response(
+        "jackal_reply",
+        vec![(MSG_RESPONSE_ATTRIBUTE, data.to_base64())],
+    ))
 }
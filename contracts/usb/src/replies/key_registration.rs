@@ -0,0 +1,17 @@
+use abstract_app::sdk::AbstractResponse;
+use cosmwasm_std::{DepsMut, Env, Reply, SubMsgResult};
+
+use crate::contract::{Usb, UsbResult};
+
+/// The registration of a posted key in the usb-adapter failed. The key stays cached in the app,
+/// the failure is only reported so it can't revert the dispatch or callback that posted the key.
+pub fn key_registration_reply(_deps: DepsMut, _env: Env, app: Usb, reply: Reply) -> UsbResult {
+    let error = match reply.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => String::new(),
+    };
+    Ok(app.custom_response(
+        "key_registration_reply",
+        vec![("key_registration_error", error)],
+    ))
+}
@@ -1,8 +1,13 @@
 mod instantiate;
 mod jackal;
+mod key_registration;
 
 pub use jackal::jackal_reply;
 pub use instantiate::instantiate_reply;
+pub use key_registration::key_registration_reply;
 
 pub const INSTANTIATE_REPLY_ID: u64 = 1u64;
 pub const JACKAL_MSG_REPLY_ID: u64 = 2u64;
+pub const KEY_REGISTRATION_REPLY_ID: u64 = 3u64;
+/// attribute holding the base64 encoded response of a jackal msg
+pub const MSG_RESPONSE_ATTRIBUTE: &str = "msg_response";
@@ -1,7 +1,432 @@
-use cw_storage_plus::Item;
+use abstract_app::objects::chain_name::ChainName;
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Empty, StdResult, Timestamp, Uint128, Uint256};
+use cw_storage_plus::{Item, Map};
+use usb::{
+    helpers::{AccessMap, FilePath},
+    versions::ProtocolVersion,
+    JackalMsgKind, JackalMsgResponse, JackalQuery, JackalQueryResponse,
+};
 
 #[cosmwasm_schema::cw_serde]
-pub struct Config {}
+pub struct Config {
+    /// canine-chain version of the jackal network msgs are sent to
+    #[serde(default)]
+    pub protocol_version: ProtocolVersion,
+    /// callers other than the admin allowed to send jackal msgs
+    #[serde(default)]
+    pub authorized: Vec<Authorization>,
+    /// name of the jackal chain as registered on the ibc-client, ex: `jackal`
+    #[serde(default = "default_host_chain")]
+    pub host_chain: ChainName,
+    /// bech32 prefix of addresses on the jackal chain, ex: `jkl`
+    #[serde(default = "default_bech32_prefix")]
+    pub bech32_prefix: String,
+    /// denom storage is paid with when a msg leaves it empty
+    #[serde(default = "default_payment_denom")]
+    pub payment_denom: String,
+    /// price of storage, used to fund the remote proxy for storage purchases
+    #[serde(default)]
+    pub storage_pricing: Option<StoragePricing>,
+    /// renewal of the storage plan issued by `Tick`, requires a storage pricing
+    #[serde(default)]
+    pub renewal: Option<RenewalPolicy>,
+    /// limits every jackal msg is checked against before it is dispatched
+    #[serde(default)]
+    pub msg_limits: MsgLimits,
+}
+
+fn default_host_chain() -> ChainName {
+    ChainName::from_chain_id("jackal-1")
+}
+
+fn default_bech32_prefix() -> String {
+    "jkl".to_string()
+}
+
+fn default_payment_denom() -> String {
+    "ujkl".to_string()
+}
+
+/// Limits on the storage msgs sent to jackal, on top of the rules of canine-chain
+#[cosmwasm_schema::cw_serde]
+pub struct MsgLimits {
+    /// smallest storage purchase, canine-chain doesn't sell less than a GB
+    pub min_bytes: i64,
+    /// largest storage purchase, unlimited when `None`
+    pub max_bytes: Option<i64>,
+    /// shortest storage purchase, canine-chain doesn't sell less than 30 days
+    pub min_duration_days: i64,
+    /// longest storage purchase, unlimited when `None`
+    pub max_duration_days: Option<i64>,
+    /// denoms storage may be paid with besides the payment denom
+    pub payment_denoms: Vec<String>,
+}
+
+impl Default for MsgLimits {
+    fn default() -> Self {
+        Self {
+            min_bytes: GB as i64,
+            max_bytes: None,
+            min_duration_days: MONTH_DAYS as i64,
+            max_duration_days: None,
+            payment_denoms: vec![],
+        }
+    }
+}
+
+/// bytes in a GB, as priced by jackal
+const GB: u128 = 1_000_000_000;
+/// days in a month of storage
+const MONTH_DAYS: u128 = 30;
+
+#[cosmwasm_schema::cw_serde]
+pub struct StoragePricing {
+    /// denom of this chain transferred to the remote proxy, arriving on jackal as the payment denom
+    pub funding_denom: String,
+    /// price of 1 GB of storage for 30 days, in `funding_denom`
+    pub price_per_gb_month: Uint128,
+}
+
+impl StoragePricing {
+    /// price of `bytes` of storage for `duration_days`, rounded up
+    pub fn cost(&self, bytes: i64, duration_days: i64) -> StdResult<Uint128> {
+        storage_cost(self.price_per_gb_month, bytes, duration_days)
+    }
+}
+
+/// price of `bytes` of storage for `duration_days` at `price_per_gb_month`, rounded up
+fn storage_cost(price_per_gb_month: Uint128, bytes: i64, duration_days: i64) -> StdResult<Uint128> {
+    let bytes = u128::try_from(bytes).unwrap_or_default();
+    let duration_days = u128::try_from(duration_days).unwrap_or_default();
+    let units = Uint256::from(bytes).checked_mul(duration_days.into())?;
+    let per = Uint256::from(GB * MONTH_DAYS);
+    let cost = Uint256::from(price_per_gb_month)
+        .checked_mul(units)?
+        .checked_add(per - Uint256::one())?
+        / per;
+    Ok(cost.try_into()?)
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct RenewalPolicy {
+    /// renew the plan once it expires in less than this many days
+    pub days_before_expiry: u64,
+    /// duration of the renewed plan
+    pub duration_days: i64,
+    /// size of the renewed plan, the size of the current plan when `None`
+    pub bytes: Option<i64>,
+    /// most a renewal may cost, in the funding denom of the storage pricing
+    pub max_spend: Uint128,
+}
+
+/// Active storage plan of the account on jackal
+#[cosmwasm_schema::cw_serde]
+pub struct StoragePlan {
+    pub bytes: i64,
+    /// time the plan runs out
+    pub expires: Timestamp,
+    pub payment_denom: String,
+}
+
+/// Storage bought for the account by a `BuyStorage` or `UpgradeStorage` msg
+#[cosmwasm_schema::cw_serde]
+pub struct StoragePurchase {
+    pub bytes: i64,
+    pub duration_days: i64,
+    pub payment_denom: String,
+}
+
+impl StoragePurchase {
+    /// plan resulting from the purchase going through at `now`
+    pub fn plan(&self, now: Timestamp) -> StoragePlan {
+        StoragePlan {
+            bytes: self.bytes,
+            expires: now.plus_days(u64::try_from(self.duration_days).unwrap_or_default()),
+            payment_denom: self.payment_denom.clone(),
+        }
+    }
+}
+
+/// Slice of the storage plan offered to other accounts
+#[cosmwasm_schema::cw_serde]
+pub struct Listing {
+    pub id: u64,
+    /// bytes left to lease
+    pub available_bytes: i64,
+    /// denom takers pay in
+    pub denom: String,
+    /// price of 1 GB for 30 days, in `denom`
+    pub price_per_gb_month: Uint128,
+    /// longest lease of the listing
+    pub max_duration_days: i64,
+}
+
+impl Listing {
+    /// price of leasing `bytes` for `duration_days`, rounded up
+    pub fn cost(&self, bytes: i64, duration_days: i64) -> StdResult<Uint128> {
+        storage_cost(self.price_per_gb_month, bytes, duration_days)
+    }
+}
+
+/// Storage bought on jackal for a taker of a listing
+#[cosmwasm_schema::cw_serde]
+pub struct Lease {
+    pub id: u64,
+    pub listing_id: u64,
+    /// address that took and paid for the lease
+    pub taker: Addr,
+    /// jackal address the storage is bought for
+    pub for_address: String,
+    pub bytes: i64,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub paid: Coin,
+    /// operation buying the storage on jackal
+    pub operation_id: u64,
+    /// active lease of the same jackal address that this lease upgrades
+    pub upgrades: Option<u64>,
+    /// storage of `for_address` on jackal once the lease is active, covering the lease it upgrades.
+    /// `None` for leases taken before it was recorded.
+    #[serde(default)]
+    pub plan: Option<StoragePlan>,
+    pub status: LeaseStatus,
+}
+
+impl Lease {
+    /// storage of `for_address` on jackal while the lease is active
+    pub fn plan(&self, payment_denom: &str) -> StoragePlan {
+        self.plan.clone().unwrap_or_else(|| StoragePlan {
+            bytes: self.bytes,
+            expires: self.end,
+            payment_denom: payment_denom.to_string(),
+        })
+    }
+}
+
+#[cosmwasm_schema::cw_serde]
+pub enum LeaseStatus {
+    /// the storage purchase is on its way to jackal
+    Pending,
+    Active,
+    /// the storage purchase failed, the taker can cancel the lease for a refund
+    Failed,
+    Cancelled,
+    /// replaced by a later lease of the same jackal address
+    Upgraded,
+    /// an active lease past its end, only reported by queries
+    Expired,
+}
+
+/// File or folder of the accounts file tree on jackal, as posted by a succeeded operation
+#[cosmwasm_schema::cw_serde]
+pub struct FileEntry {
+    pub path: FilePath,
+    /// FID of the file, empty for a folder
+    pub fid: String,
+    pub tracking_number: String,
+    /// size of the file in bytes, when known to the app
+    pub size: Option<u64>,
+    pub parent: FilePath,
+    /// viewers, with the file key encrypted to each of them
+    pub viewers: AccessMap,
+    /// editors, with the file key encrypted to each of them
+    pub editors: AccessMap,
+    /// height the path was first posted at
+    pub created_height: u64,
+    /// height the entry last changed at
+    pub updated_height: u64,
+}
+
+/// Change to the file index, applied once the operation making it succeeded
+#[cosmwasm_schema::cw_serde]
+pub enum FileChange {
+    Post {
+        path: FilePath,
+        fid: String,
+        tracking_number: String,
+        size: Option<u64>,
+        viewers: AccessMap,
+        editors: AccessMap,
+    },
+    /// remove the path and everything under it
+    Delete { path: FilePath },
+    /// add `recipient` to the viewers of the file
+    Share {
+        path: FilePath,
+        recipient: String,
+        wrapped_key: String,
+    },
+    /// remove `recipient` from the viewers of the file
+    Revoke { path: FilePath, recipient: String },
+    /// the file at merkle path `address` was handed over to another owner, remove it and everything under it
+    Disown { address: String },
+}
+
+/// Caller allowed to send jackal msgs on behalf of the account
+#[cosmwasm_schema::cw_serde]
+pub enum Caller {
+    Address(String),
+    /// module installed on the account, by module id
+    Module(String),
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Authorization {
+    pub caller: Caller,
+    /// msg kinds the caller may send, any kind when `None`
+    pub kinds: Option<Vec<JackalMsgKind>>,
+    /// whether the caller may query jackal, always the case when `kinds` is `None`
+    #[serde(default)]
+    pub queries: bool,
+}
+
+impl Authorization {
+    /// whether `kind` is covered by this authorization
+    pub fn allows(&self, kind: JackalMsgKind) -> bool {
+        self.kinds
+            .as_ref()
+            .map_or(true, |kinds| kinds.contains(&kind))
+    }
+
+    /// whether queries of jackal are covered by this authorization
+    pub fn allows_queries(&self) -> bool {
+        self.kinds.is_none() || self.queries
+    }
+}
+
+/// Batch of jackal msgs dispatched by the app
+#[cosmwasm_schema::cw_serde]
+pub struct Operation {
+    pub id: u64,
+    /// kind of each msg of the batch, in order
+    pub kinds: Vec<JackalMsgKind>,
+    pub status: OperationStatus,
+    /// decoded response of each msg, once the batch succeeded
+    pub responses: Vec<JackalMsgResponse>,
+    /// times the batch was sent again after failing or timing out
+    #[serde(default)]
+    pub retries: u32,
+    /// height the batch was dispatched at
+    pub created_height: u64,
+    /// height the status last changed at
+    pub updated_height: u64,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub enum OperationStatus {
+    /// sent over ibc, waiting for the callback
+    Pending,
+    Succeeded,
+    Failed {
+        error: String,
+    },
+    /// the ibc packet timed out before reaching jackal
+    TimedOut,
+}
+
+/// Query of jackal sent by the app, with its response once answered
+#[cosmwasm_schema::cw_serde]
+pub struct RemoteQuery {
+    pub id: u64,
+    /// query as sent, with the defaults filled in
+    pub query: JackalQuery,
+    pub status: OperationStatus,
+    /// decoded response, once the query succeeded
+    pub response: Option<JackalQueryResponse>,
+    /// height the query was sent at
+    pub created_height: u64,
+    /// height the status last changed at
+    pub updated_height: u64,
+}
+
+/// Statistics of the batches dispatched by the app
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct Stats {
+    /// batches dispatched, retries aren't counted again
+    pub batches: u64,
+    /// msgs dispatched per kind, sorted by kind
+    pub msgs: Vec<MsgCount>,
+    /// bytes of storage bought by succeeded batches, for the account and its leases
+    pub bytes_purchased: Uint128,
+    /// funds sent along the succeeded batches to pay on jackal, per denom
+    pub funds_sent: Vec<Coin>,
+    /// latest batch sent to jackal, retries included
+    pub last_dispatch: Option<LastDispatch>,
+}
+
+impl Stats {
+    /// count a dispatched msg of `kind`
+    pub fn count_msg(&mut self, kind: JackalMsgKind) {
+        match self.msgs.binary_search_by_key(&kind, |count| count.kind) {
+            Ok(index) => self.msgs[index].count += 1,
+            Err(index) => self.msgs.insert(index, MsgCount { kind, count: 1 }),
+        }
+    }
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct MsgCount {
+    pub kind: JackalMsgKind,
+    pub count: u64,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LastDispatch {
+    pub operation_id: u64,
+    pub height: u64,
+    /// status of the operation, updated by its callback
+    pub status: OperationStatus,
+}
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const COUNT: Item<i32> = Item::new("count");
+pub const STATS: Item<Stats> = Item::new("stats");
+/// Bytes of storage bought by pending operations, counted in the stats once they succeed
+pub const PURCHASED_BYTES: Map<u64, Uint128> = Map::new("purchased_bytes");
+/// Funds sent along pending operations and their retries, counted in the stats once they succeed
+pub const DISPATCHED_FUNDS: Map<u64, Vec<Coin>> = Map::new("dispatched_funds");
+/// Cache of the accounts proxy address on each remote host chain
+pub const REMOTE_PROXIES: Map<&ChainName, String> = Map::new("remote_proxies");
+/// Id of the next dispatched operation
+pub const NEXT_OPERATION_ID: Item<u64> = Item::new("next_operation_id");
+pub const OPERATIONS: Map<u64, Operation> = Map::new("operations");
+/// Encoded msgs of each operation, sent again on retry
+pub const OPERATION_MSGS: Map<u64, Vec<CosmosMsg>> = Map::new("operation_msgs");
+/// Storage bought by pending operations, recorded as the plan once they succeed
+pub const STORAGE_PURCHASES: Map<u64, StoragePurchase> = Map::new("storage_purchases");
+pub const STORAGE_PLAN: Item<StoragePlan> = Item::new("storage_plan");
+/// Operation of the last renewal issued by `Tick`
+pub const RENEWAL: Item<u64> = Item::new("renewal");
+pub const NEXT_LISTING_ID: Item<u64> = Item::new("next_listing_id");
+pub const LISTINGS: Map<u64, Listing> = Map::new("listings");
+/// Bytes available in the open listings
+pub const LISTED_BYTES: Item<i64> = Item::new("listed_bytes");
+/// Bytes leased from the listings, by the second their leases end
+pub const LEASED_BYTES: Map<u64, i64> = Map::new("leased_bytes");
+pub const NEXT_LEASE_ID: Item<u64> = Item::new("next_lease_id");
+pub const LEASES: Map<u64, Lease> = Map::new("leases");
+/// Lease settled by each operation
+pub const LEASE_OPERATIONS: Map<u64, u64> = Map::new("lease_operations");
+/// Latest active lease of each jackal address
+pub const ACTIVE_LEASES: Map<&str, u64> = Map::new("active_leases");
+/// Index of the accounts file tree, by plain path
+pub const FILES: Map<&str, FileEntry> = Map::new("files");
+/// Indexed paths, by merkle path
+pub const MERKLE_PATHS: Map<&str, FilePath> = Map::new("merkle_paths");
+/// Indexed paths of each folder, by folder and child name
+pub const CHILDREN: Map<(&str, &str), Empty> = Map::new("children");
+/// Changes to the file index made by pending operations
+pub const FILE_CHANGES: Map<u64, Vec<FileChange>> = Map::new("file_changes");
+/// ECIES public key the account posted on jackal, hex encoded
+pub const PUBLIC_KEY: Item<String> = Item::new("public_key");
+/// Public key posted by pending operations, cached once they succeed
+pub const KEY_POSTS: Map<u64, String> = Map::new("key_posts");
+/// Times a failed or timed out operation can be retried
+pub const MAX_RETRIES: u32 = 3;
+/// Operation whose msg responses are being collected by the replies of a local dispatch
+pub const DISPATCHING: Item<u64> = Item::new("dispatching");
+/// Id of the next query of jackal
+pub const NEXT_REMOTE_QUERY_ID: Item<u64> = Item::new("next_remote_query_id");
+pub const REMOTE_QUERIES: Map<u64, RemoteQuery> = Map::new("remote_queries");
+/// Latest succeeded remote query of each query, by its json encoding
+pub const LATEST_ANSWERS: Map<&str, u64> = Map::new("latest_answers");
@@ -1,28 +1,36 @@
 use abstract_cw_orch_polytone::Polytone;
-use abstract_interface::{
-    Abstract, AbstractAccount, AccountDetails, AccountFactoryExecFns, ManagerQueryFns,
-};
+use abstract_interface::{Abstract, AccountFactoryExecFns, ManagerExecFns};
 // Use prelude to get all the necessary imports
+use cosmwasm_std::{to_json_binary, Binary};
 use cw_orch::{anyhow::Result, contract::Deploy, prelude::*};
 use cw_orch_interchain::{
     IbcQueryHandler, InterchainEnv, InterchainError, MockBech32InterchainEnv,
 };
-use polytone::handshake::POLYTONE_VERSION;
+use ibc_relayer_types::core::ics24_host::identifier::PortId;
+use polytone::{callbacks::ExecutionResponse, handshake::POLYTONE_VERSION};
+use usb::versions::ProtocolVersion;
 use usb_plugin::{
     contract::interface::UsbInterface,
-    msg::{UsbExecuteMsgFns, UsbInstantiateMsg, UsbQueryMsgFns},
-    UsbError, USB_NAMESPACE,
+    msg::{ExecuteMsg, UsbExecuteMsgFns, UsbInstantiateMsg, UsbQueryMsgFns},
+    state::OPERATION_MSGS,
+    UsbError, USB_ID, USB_NAMESPACE,
 };
 
 use abstract_app::{
-    objects::{chain_name::ChainName, namespace::Namespace},
+    objects::{chain_name::ChainName, module::ModuleInfo, namespace::Namespace},
     std::{
-        ibc_client::{ExecuteMsgFns, QueryMsgFns},
+        ans_host::ExecuteMsgFns as AnsHostExecuteMsgFns,
+        ibc::{CallbackResult, IbcResponseMsg},
+        ibc_client::{self, ExecuteMsgFns, QueryMsgFns},
         ibc_host::ExecuteMsgFns as IbcHostExecuteMsgFunctions,
+        manager::ModuleInstallConfig,
+        objects::UncheckedChannelEntry,
+        proxy, ICS20, PROXY,
     },
 };
 use abstract_client::{AbstractClient, Application, Environment};
 
+#[allow(dead_code)]
 struct TestEnv<Env: CwEnv> {
     env: Env,
     abs: AbstractClient<Env>,
@@ -34,6 +42,20 @@ pub const TEST_ACCOUNT_NAME: &str = "account-test";
 pub const TEST_ACCOUNT_DESCRIPTION: &str = "Description of an account";
 pub const TEST_ACCOUNT_LINK: &str = "https://google.com";
 
+/// The msg the App is installed with, on the local accounts and on remote ones alike
+fn instantiate_msg(host_chain: &str) -> UsbInstantiateMsg {
+    UsbInstantiateMsg {
+        protocol_version: ProtocolVersion::V3,
+        authorized: vec![],
+        host_chain: host_chain.to_string(),
+        bech32_prefix: "jkl".to_string(),
+        payment_denom: "ujkl".to_string(),
+        storage_pricing: None,
+        renewal: None,
+        msg_limits: None,
+    }
+}
+
 impl<Env: CwEnv> TestEnv<Env> {
     /// Set up the test environment with an Account that has the App installed
     fn setup(env: Env) -> Result<TestEnv<Env>> {
@@ -51,7 +73,7 @@ impl<Env: CwEnv> TestEnv<Env> {
             .build()?;
         // Install USB Module
         let app = acc.install_app_with_dependencies::<UsbInterface<_>>(
-            &UsbInstantiateMsg {},
+            &instantiate_msg("jackal"),
             Empty {},
             &[],
         )?;
@@ -62,7 +84,7 @@ impl<Env: CwEnv> TestEnv<Env> {
             .build()?;
         // Install USB
         let app2 = acc2.install_app_with_dependencies::<UsbInterface<_>>(
-            &UsbInstantiateMsg {},
+            &instantiate_msg("jackal"),
             Empty {},
             &[],
         )?;
@@ -82,9 +104,40 @@ impl<Env: CwEnv> TestEnv<Env> {
     }
 }
 
+/// Origin and jackal chains, each with the app installed, connected over ibc with polytone and abstract
+fn ibc_test_env() -> Result<(
+    MockBech32InterchainEnv,
+    TestEnv<MockBech32>,
+    TestEnv<MockBech32>,
+)> {
+    let interchain = MockBech32InterchainEnv::new(vec![
+        ("juno-1", "juno1fxccvvhhy43tvet2ah7jqwq4cwl9k3dx2kyce9"),
+        ("jackal-1", "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4"),
+    ]);
+    let bs_env = TestEnv::setup(interchain.chain("juno-1")?)?;
+    let jkl_env = TestEnv::setup(interchain.chain("jackal-1")?)?;
+    bs_env.enable_ibc()?;
+    jkl_env.enable_ibc()?;
+    ibc_connect_polytone_and_abstract(&interchain, "juno-1", "jackal-1")?;
+    Ok((interchain, bs_env, jkl_env))
+}
+
+/// Successful execution of a batch on jackal, as reported by the callback
+fn executed() -> ExecutionResponse {
+    ExecutionResponse {
+        executed_by: "remote-proxy".to_string(),
+        result: vec![],
+    }
+}
+
 mod basic_functions {
     use super::*;
-    use usb::JackalMsg;
+    use usb::{
+        crypto::{encrypt_file, public_key, FileDetails, FileKey},
+        helpers::{hash_and_hex, owner_address, AccessMap, FilePath},
+        JackalMsg,
+    };
+    use usb_plugin::state::MsgLimits;
 
     // Jackal storage encryption workflow
     // 1. generate random key offline
@@ -92,70 +145,2094 @@ mod basic_functions {
     // 3. encrypt key with wallet pubkey & signature, store to x/filetree
     #[test]
     fn save_file() -> Result<()> {
-        // a. Create a sender and mock env for each chain
-        let interchain = MockBech32InterchainEnv::new(vec![
-            ("juno-1", "juno1fxccvvhhy43tvet2ah7jqwq4cwl9k3dx2kyce9"),
-            ("jackal-1", "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4"),
-        ]);
-        // b. Create Test Environments
-        let bs_env = TestEnv::setup(interchain.chain("juno-1")?)?;
-        let jkl_env = TestEnv::setup(interchain.chain("jackal-1")?)?;
+        // a. - d. Create a test environment for each chain, with IBC enabled and the chains connected to each other
+        let (interchain, bs_env, _) = ibc_test_env()?;
 
-        // c. Enable IBC
-        bs_env.enable_ibc()?;
-        jkl_env.enable_ibc()?;
+        // e. Create the accounts proxy on jackal, used to sign jackal msgs.
+        // cw-orch stores one address per module id, so the app interface points to the last installed app (client2)
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
 
-        // d. Connect Chains To Each Other
-        let ibc_client = ibc_connect_polytone_and_abstract(&interchain, "juno-1", "jackal-1")?;
-
-        let bs_client = bs_env.client1;
-        let jkl_client = jkl_env.client1;
+        // 1. generate random key offline
+        let file_key = FileKey::generate();
+        // 2. encrypt file with key, the result is uploaded to a storage provider
+        let contents = b"hello jackal";
+        let details = FileDetails {
+            name: "test.txt".to_string(),
+            last_modified: 0,
+            file_type: "text/plain".to_string(),
+            size: contents.len() as u64,
+        };
+        let _encrypted = encrypt_file(&file_key, &details, contents)?;
+        // 3. encrypt key with the wallet pubkey posted to x/filetree
+        let pubkey = public_key(&[7u8; 32])?;
+        let viewers = AccessMap::new().with(
+            "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4",
+            file_key.wrap_for(&pubkey)?,
+        );
 
+        // files are addressed by their merkle path, owners by the hash of their address
+        // and sharing or ownership changes by the owner address the file is stored under
+        let address = FilePath::new("s/home/test")?.merkle_path();
+        let owner = hash_and_hex("jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4");
+        let file_owner = owner_address(&address, "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4");
         let msg = JackalMsg::MakeRoot {
-            editors: "test".to_string(),
-            viewers: "test".to_string(),
+            editors: AccessMap::new(),
+            viewers: viewers.clone(),
             tracking_number: "test".to_string(),
         };
-        let msg2 = JackalMsg::PostKey {
-            key: "test".to_string(),
-        };
+        let msg2 = JackalMsg::PostKey { key: pubkey };
         let msg3 = JackalMsg::AddViewers {
-            viewer_ids: "test".to_string(),
-            viewer_keys: "test".to_string(),
-            address: "test".to_string(),
-            owner: "test".to_string(),
+            viewers,
+            tracking_number: "test".to_string(),
+            address: address.clone(),
+            owner: file_owner.clone(),
         };
         let msg4 = JackalMsg::BuyStorage {
-            for_address: "test".to_string(),
+            for_address: "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4".to_string(),
             duration_days: 30,
-            bytes: 0,
-            payment_denom: "uterp".to_string(),
+            bytes: 1_000_000_000,
+            payment_denom: "ujkl".to_string(),
         };
         let msg5 = JackalMsg::CancelContract {
             cid: "test".to_string(),
         };
         let msg6 = JackalMsg::SignContract {
             cid: "test".to_string(),
+            pay_once: false,
         };
         let msg7 = JackalMsg::UpgradeStorage {
-            for_address: "test".to_string(),
+            for_address: "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4".to_string(),
             duration_days: 30,
-            bytes: 0,
-            payment_denom: "ubtsg".to_string(),
+            bytes: 1_000_000_000,
+            payment_denom: String::new(),
         };
         let msg9 = JackalMsg::DeleteViewers {
-            viewer_ids: "test".to_string(),
-            address: "test".to_string(),
-            owner: "test".to_string(),
+            viewers: vec!["jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4".to_string()],
+            tracking_number: "test".to_string(),
+            address: address.clone(),
+            owner: file_owner.clone(),
         };
 
-        let res = bs_client.jackal_msgs(vec![msg, msg2, msg3, msg4, msg5, msg6, msg7, msg9]);
+        let msg10 = JackalMsg::Delete {
+            path: FilePath::new("s/home/test")?,
+            account: owner.clone(),
+        };
+
+        let res = bs_client.jackal_msgs(vec![msg, msg2, msg3, msg4, msg5, msg6, msg7, msg9, msg10]);
         assert!(res.is_ok());
 
+        let editors = vec![
+            JackalMsg::AddEditors {
+                editors: AccessMap::new()
+                    .with("jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4", "test"),
+                tracking_number: "test".to_string(),
+                address: address.clone(),
+                owner: file_owner.clone(),
+            },
+            JackalMsg::RemoveEditors {
+                editors: vec!["jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4".to_string()],
+                tracking_number: "test".to_string(),
+                address: address.clone(),
+                owner: file_owner.clone(),
+            },
+            JackalMsg::ResetEditors {
+                address: address.clone(),
+                owner: file_owner.clone(),
+            },
+            JackalMsg::ResetViewers {
+                address: address.clone(),
+                owner: file_owner.clone(),
+            },
+            JackalMsg::ChangeOwner {
+                address: address.clone(),
+                owner: file_owner.clone(),
+                new_owner: "test".to_string(),
+            },
+            JackalMsg::DeleteFile {
+                hash_path: address.clone(),
+                account: owner.clone(),
+            },
+            JackalMsg::ProvisionFileTree {
+                editors: AccessMap::new(),
+                viewers: AccessMap::new(),
+                tracking_number: "test".to_string(),
+            },
+        ];
+        let res = bs_client.jackal_msgs(editors);
+        assert!(res.is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn upload_file() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        let viewers =
+            AccessMap::new().with("jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4", "wrapped_key");
+        bs_client.upload_file(
+            AccessMap::new(),
+            "jklf1fid".to_string(),
+            FilePath::new("s/home/file.txt")?,
+            viewers.clone(),
+            None,
+        )?;
+
+        // a file can't replace the root folder
+        let err = bs_client
+            .upload_file(
+                AccessMap::new(),
+                "jklf1fid".to_string(),
+                FilePath::root(),
+                viewers,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InvalidHashParent {
+                index: 0,
+                path: "s".to_string(),
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_msgs_fail_with_their_index() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        let buy = |bytes: i64, duration_days: i64, payment_denom: &str| JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days,
+            bytes,
+            payment_denom: payment_denom.to_string(),
+        };
+        let post_key = JackalMsg::PostKey {
+            key: "02ab".to_string(),
+        };
+        let assert_rejected = |msgs: Vec<JackalMsg>, error: UsbError| {
+            let err = bs_client.jackal_msgs(msgs).unwrap_err();
+            assert_eq!(err.root().to_string(), error.to_string());
+        };
+
+        assert_rejected(
+            vec![post_key.clone(), buy(0, 30, "")],
+            UsbError::StorageBytes {
+                index: 1,
+                bytes: 0,
+                min: 1_000_000_000,
+                max: None,
+            },
+        );
+        assert_rejected(
+            vec![buy(1_000_000_000, 7, "")],
+            UsbError::StorageDuration {
+                index: 0,
+                duration_days: 7,
+                min: 30,
+                max: None,
+            },
+        );
+        assert_rejected(
+            vec![
+                post_key.clone(),
+                post_key.clone(),
+                buy(1_000_000_000, 30, "uterp"),
+            ],
+            UsbError::UnknownPaymentDenom {
+                index: 2,
+                denom: "uterp".to_string(),
+            },
+        );
+        assert_rejected(
+            vec![JackalMsg::BuyStorage {
+                for_address: "juno1taker".to_string(),
+                duration_days: 30,
+                bytes: 1_000_000_000,
+                payment_denom: String::new(),
+            }],
+            UsbError::InvalidStorageAddress {
+                index: 0,
+                address: "juno1taker".to_string(),
+            },
+        );
+        assert_rejected(
+            vec![
+                post_key.clone(),
+                JackalMsg::SignContract {
+                    cid: String::new(),
+                    pay_once: false,
+                },
+            ],
+            UsbError::EmptyCid { index: 1 },
+        );
+        assert_rejected(
+            vec![JackalMsg::PostKey {
+                key: "not hex".to_string(),
+            }],
+            UsbError::InvalidPublicKey {
+                index: 0,
+                key: "not hex".to_string(),
+            },
+        );
+        assert_rejected(
+            vec![JackalMsg::PostFile {
+                path: FilePath::root(),
+                contents: "jklf1fid".to_string(),
+                viewers: AccessMap::new(),
+                editors: AccessMap::new(),
+                tracking_number: "test".to_string(),
+            }],
+            UsbError::InvalidHashParent {
+                index: 0,
+                path: "s".to_string(),
+            },
+        );
+        assert_rejected(
+            vec![JackalMsg::DeleteFile {
+                hash_path: "s/home/file.txt".to_string(),
+                account: String::new(),
+            }],
+            UsbError::InvalidHash {
+                index: 0,
+                field: "hash_path".to_string(),
+                value: "s/home/file.txt".to_string(),
+            },
+        );
+        assert_eq!(bs_client.operations(None, None)?.operations.len(), 0);
+
+        // limits are configured by the admin, on top of the rules of canine-chain
+        let limits = MsgLimits {
+            max_bytes: Some(5_000_000_000),
+            payment_denoms: vec!["uterp".to_string()],
+            ..MsgLimits::default()
+        };
+        let err = bs_client
+            .update_config(
+                None,
+                None,
+                None,
+                Some(MsgLimits {
+                    max_bytes: Some(1),
+                    ..limits.clone()
+                }),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InvalidMsgLimits("maximum below the minimum".to_string()).to_string()
+        );
+        // nor loosen them
+        let err = bs_client
+            .update_config(
+                None,
+                None,
+                None,
+                Some(MsgLimits {
+                    min_duration_days: 7,
+                    ..limits.clone()
+                }),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InvalidMsgLimits(
+                "canine-chain doesn't sell storage of less than 1000000000 bytes or 30 days"
+                    .to_string()
+            )
+            .to_string()
+        );
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            Some(limits.clone()),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        assert_eq!(bs_client.config()?.msg_limits, limits);
+        assert_rejected(
+            vec![buy(6_000_000_000, 30, "")],
+            UsbError::StorageBytes {
+                index: 0,
+                bytes: 6_000_000_000,
+                min: 1_000_000_000,
+                max: Some(5_000_000_000),
+            },
+        );
+        bs_client.jackal_msgs(vec![buy(5_000_000_000, 30, "uterp")])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn send_without_remote_account() -> Result<()> {
+        let (_, bs_env, _) = ibc_test_env()?;
+
+        // no remote account was created, so there is no jackal signer for this account
+        let err = bs_env
+            .client2
+            .jackal_msgs(vec![JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::RemoteProxyNotFound("jackal".to_string()).to_string()
+        );
+
+        Ok(())
+    }
+}
+
+mod config {
+    use super::*;
+    use usb::{error::JackalError, helpers::AccessMap, JackalMsg, JackalMsgKind};
+
+    #[test]
+    fn protocol_version_selects_msg_set() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        assert_eq!(bs_client.config()?.protocol_version, ProtocolVersion::V3);
+
+        // only the admin can change the protocol version
+        let stranger = bs_env.env.addr_make("stranger");
+        assert!(bs_client
+            .call_as(&stranger)
+            .update_config(
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(ProtocolVersion::V4),
+                None,
+                None
+            )
+            .is_err());
+
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(ProtocolVersion::V4),
+            None,
+            None,
+        )?;
+        assert_eq!(bs_client.config()?.protocol_version, ProtocolVersion::V4);
+
+        // storage contracts don't exist on v4
+        let err = bs_client
+            .jackal_msgs(vec![JackalMsg::SignContract {
+                cid: "test".to_string(),
+                pay_once: false,
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::Jackal(JackalError::UnsupportedMsg {
+                msg: JackalMsgKind::SignContract,
+                version: ProtocolVersion::V4,
+            })
+            .to_string()
+        );
+
+        // shared msgs are still dispatched
+        bs_client.jackal_msgs(vec![JackalMsg::MakeRoot {
+            editors: AccessMap::new(),
+            viewers: AccessMap::new(),
+            tracking_number: "test".to_string(),
+        }])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn host_chain_must_be_registered() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let config = bs_client.config()?;
+        assert_eq!(config.host_chain, "jackal");
+        assert_eq!(config.bech32_prefix, "jkl");
+        assert_eq!(config.payment_denom, "ujkl");
+
+        // no ibc connection to a jackal testnet
+        let err = bs_client
+            .update_config(
+                None,
+                None,
+                Some("jackaltest".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::HostNotRegistered("jackaltest".to_string()).to_string()
+        );
+        bs_client.update_config(
+            None,
+            None,
+            Some("jackal".to_string()),
+            None,
+            Some("uatom".to_string()),
+            None,
+            None,
+            None,
+        )?;
+        assert_eq!(bs_client.config()?.payment_denom, "uatom");
+
+        // a storage purchase without denom is paid with the configured one
+        bs_client.jackal_msgs(vec![JackalMsg::BuyStorage {
+            for_address: "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4".to_string(),
+            duration_days: 30,
+            bytes: 1_000_000_000,
+            payment_denom: String::new(),
+        }])?;
+
+        // the remote proxy must live on a chain using the configured prefix
+        bs_client.update_config(
+            None,
+            Some("cosmos".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let err = bs_client
+            .jackal_msgs(vec![JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            }])
+            .unwrap_err();
+        assert!(err
+            .root()
+            .to_string()
+            .contains("doesn't use the configured bech32 prefix cosmos"));
+
+        Ok(())
+    }
+}
+
+mod local_dispatch {
+    use super::*;
+    use usb::{JackalMsg, JackalQuery};
+    use usb_plugin::state::OperationStatus;
+
+    #[test]
+    fn account_on_jackal_sends_msgs_without_ibc() -> Result<()> {
+        let interchain = MockBech32InterchainEnv::new(vec![(
+            "jackal-1",
+            "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4",
+        )]);
+        // no ibc infrastructure and no remote account, the proxy on jackal signs the msgs
+        let jkl_env = TestEnv::setup(interchain.chain("jackal-1")?)?;
+        let proxy = jkl_env.client2.account().proxy()?;
+
+        // the mock chain has no jackal modules, so the stargate msg is rejected once the proxy broadcasts it
+        let err = jkl_env
+            .client2
+            .jackal_msgs(vec![JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            }])
+            .unwrap_err();
+        let err = format!("{err:?}");
+        assert!(err.contains("/canine_chain.filetree.MsgPostKey"));
+        assert!(err.contains(&format!("from Addr(\"{proxy}\")")));
+
+        // a batch that goes through succeeded right away
+        jkl_env.client2.jackal_msgs(vec![])?;
+        let stats = jkl_env.client2.stats()?;
+        assert_eq!(stats.batches, 1);
+        assert_eq!(
+            stats.last_dispatch.unwrap().status,
+            OperationStatus::Succeeded
+        );
+
+        // queries are answered right away, the mock chain has no jackal modules to answer them
+        jkl_env.client2.query_jackal(JackalQuery::PubKey {
+            address: String::new(),
+        })?;
+        let remote_query = jkl_env.client2.remote_query(0)?;
+        assert_eq!(
+            remote_query.query,
+            JackalQuery::PubKey {
+                address: proxy.to_string(),
+            }
+        );
+        assert!(matches!(
+            remote_query.status,
+            OperationStatus::Failed { .. }
+        ));
+
+        Ok(())
+    }
+}
+
+mod operations {
+    use super::*;
+    use usb::{JackalMsg, JackalMsgKind};
+    use usb_plugin::state::OperationStatus;
+
+    #[test]
+    fn dispatched_batches_are_tracked() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        // an empty batch executes on jackal without any stargate msg
+        let tx = bs_client.jackal_msgs(vec![])?;
+        let operation = bs_client.operation(0)?;
+        assert_eq!(operation.status, OperationStatus::Pending);
+        interchain.check_ibc("juno-1", tx)?.into_result()?;
+        let operation = bs_client.operation(0)?;
+        assert_eq!(operation.status, OperationStatus::Succeeded);
+        assert!(operation.responses.is_empty());
+
+        // the mock chain has no jackal modules, so the batch fails on the remote account
+        let tx = bs_client.jackal_msgs(vec![JackalMsg::PostKey {
+            key: "02ab".to_string(),
+        }])?;
+        // the remote execution fails, so the ack is an error
+        let _ = interchain.wait_ibc("juno-1", tx)?;
+        let operation = bs_client.operation(1)?;
+        assert_eq!(operation.kinds, vec![JackalMsgKind::PostKey]);
+        assert!(matches!(operation.status, OperationStatus::Failed { .. }));
+
+        let operations = bs_client.operations(None, None)?.operations;
+        assert_eq!(
+            operations.iter().map(|op| op.id).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        let operations = bs_client.operations(Some(1), None)?.operations;
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].id, 0);
+        let operations = bs_client.operations(None, Some(0))?.operations;
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].id, 1);
+
+        Ok(())
+    }
+}
+
+mod retries {
+    use super::*;
+    use usb::JackalMsg;
+    use usb_plugin::state::{OperationStatus, MAX_RETRIES};
+
+    #[test]
+    fn failed_batches_are_retried_up_to_the_limit() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        // a succeeded batch is never sent again
+        let tx = bs_client.jackal_msgs(vec![])?;
+        interchain.check_ibc("juno-1", tx)?.into_result()?;
+        let err = bs_client.retry_operation(0).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::OperationSucceeded(0).to_string()
+        );
+
+        // the mock chain has no jackal modules, so every attempt fails on the remote account
+        let tx = bs_client.jackal_msgs(vec![JackalMsg::PostKey {
+            key: "02ab".to_string(),
+        }])?;
+        let err = bs_client.retry_operation(1).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::OperationPending(1).to_string()
+        );
+        let _ = interchain.wait_ibc("juno-1", tx)?;
+
+        for retries in 1..=MAX_RETRIES {
+            let tx = bs_client.retry_operation(1)?;
+            let operation = bs_client.operation(1)?;
+            assert_eq!(operation.status, OperationStatus::Pending);
+            assert_eq!(operation.retries, retries);
+            let _ = interchain.wait_ibc("juno-1", tx)?;
+            let operation = bs_client.operation(1)?;
+            assert!(matches!(operation.status, OperationStatus::Failed { .. }));
+        }
+
+        let err = bs_client.retry_operation(1).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::RetryLimit {
+                id: 1,
+                max: MAX_RETRIES
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+}
+
+mod funding {
+    use super::*;
+    use usb::JackalMsg;
+    use usb_plugin::state::StoragePricing;
+
+    #[test]
+    fn storage_purchases_fund_the_remote_proxy() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        // funds are sent over the ics20 channel registered for jackal
+        connect_ics20(&interchain, "juno-1", "jackal-1", "jackal")?;
+
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(StoragePricing {
+                funding_denom: "ujuno".to_string(),
+                price_per_gb_month: 1_000_000u128.into(),
+            }),
+        )?;
+
+        // 2 GB for 30 days
+        let buy_storage = || {
+            vec![JackalMsg::BuyStorage {
+                for_address: String::new(),
+                duration_days: 30,
+                bytes: 2_000_000_000,
+                payment_denom: String::new(),
+            }]
+        };
+        let proxy = bs_client.account().proxy()?;
+        bs_env
+            .env
+            .set_balance(&proxy, vec![Coin::new(1_500_000, "ujuno")])?;
+
+        let err = bs_client.jackal_msgs(buy_storage()).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InsufficientFunds {
+                denom: "ujuno".to_string(),
+                required: 2_000_000u128.into(),
+                available: 1_500_000u128.into(),
+            }
+            .to_string()
+        );
+
+        // the account pays for the storage, the rest of its balance stays
+        bs_env
+            .env
+            .set_balance(&proxy, vec![Coin::new(2_500_000, "ujuno")])?;
+        bs_client.jackal_msgs(buy_storage())?;
+        assert_eq!(bs_env.env.query_balance(&proxy, "ujuno")?.u128(), 500_000);
+
+        // batches without storage purchases don't move funds
+        bs_client.jackal_msgs(vec![JackalMsg::PostKey {
+            key: "02ab".to_string(),
+        }])?;
+        assert_eq!(bs_env.env.query_balance(&proxy, "ujuno")?.u128(), 500_000);
+
+        Ok(())
+    }
+}
+
+mod stats {
+    use super::*;
+    use usb::{JackalMsg, JackalMsgKind};
+    use usb_plugin::state::{LastDispatch, MsgCount, OperationStatus, StoragePricing};
+
+    #[test]
+    fn dispatched_batches_are_counted() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+        connect_ics20(&interchain, "juno-1", "jackal-1", "jackal")?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let stats = bs_client.stats()?;
+        assert_eq!(stats.batches, 0);
+        assert_eq!(stats.last_dispatch, None);
+
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(StoragePricing {
+                funding_denom: "ujuno".to_string(),
+                price_per_gb_month: 1_000_000u128.into(),
+            }),
+        )?;
+        let proxy = bs_client.account().proxy()?;
+        bs_env
+            .env
+            .set_balance(&proxy, vec![Coin::new(5_000_000, "ujuno")])?;
+
+        // 2 GB for 30 days, counted once the purchase succeeded
+        bs_client.jackal_msgs(vec![
+            JackalMsg::BuyStorage {
+                for_address: String::new(),
+                duration_days: 30,
+                bytes: 2_000_000_000,
+                payment_denom: String::new(),
+            },
+            JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            },
+        ])?;
+        let stats = bs_client.stats()?;
+        assert_eq!(stats.batches, 1);
+        assert_eq!(stats.bytes_purchased.u128(), 0);
+        assert!(stats.funds_sent.is_empty());
+        let height = bs_env.env.block_info()?.height;
+        assert_eq!(
+            stats.last_dispatch,
+            Some(LastDispatch {
+                operation_id: 0,
+                height,
+                status: OperationStatus::Pending,
+            })
+        );
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        let stats = bs_client.stats()?;
+        assert_eq!(stats.bytes_purchased.u128(), 2_000_000_000);
+        assert_eq!(stats.funds_sent, vec![Coin::new(2_000_000, "ujuno")]);
+        assert_eq!(
+            stats.last_dispatch.unwrap().status,
+            OperationStatus::Succeeded
+        );
+
+        // retries update the last dispatch without counting the batch again
+        bs_client.jackal_msgs(vec![JackalMsg::PostKey {
+            key: "02ab".to_string(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 1, Err("timeout".to_string()))?;
+        assert_eq!(
+            bs_client.stats()?.last_dispatch.unwrap().status,
+            OperationStatus::TimedOut
+        );
+        bs_env.env.next_block()?;
+        bs_client.retry_operation(1)?;
+        let stats = bs_client.stats()?;
+        assert_eq!(stats.batches, 2);
+        assert_eq!(
+            stats.msgs,
+            vec![
+                MsgCount {
+                    kind: JackalMsgKind::BuyStorage,
+                    count: 1
+                },
+                MsgCount {
+                    kind: JackalMsgKind::PostKey,
+                    count: 2
+                },
+            ]
+        );
+        assert_eq!(
+            stats.last_dispatch,
+            Some(LastDispatch {
+                operation_id: 1,
+                height: height + 1,
+                status: OperationStatus::Pending,
+            })
+        );
+
+        // the funds of a failed batch aren't counted until a retry of it succeeds
+        bs_client.jackal_msgs(vec![JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days: 30,
+            bytes: 1_000_000_000,
+            payment_denom: String::new(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 2, Err("out of gas".to_string()))?;
+        assert_eq!(
+            bs_client.stats()?.funds_sent,
+            vec![Coin::new(2_000_000, "ujuno")]
+        );
+        bs_client.retry_operation(2)?;
+        dispatch_callback(&bs_env.env, &bs_client, 2, Ok(executed()))?;
+        assert_eq!(
+            bs_client.stats()?.funds_sent,
+            vec![Coin::new(3_000_000, "ujuno")]
+        );
+
+        Ok(())
+    }
+}
+
+mod renewal {
+    use super::*;
+    use usb::{JackalMsg, JackalMsgKind};
+    use usb_plugin::state::{OperationStatus, RenewalPolicy, StoragePricing};
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn tick_renews_the_storage_plan_before_expiry() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+        connect_ics20(&interchain, "juno-1", "jackal-1", "jackal")?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let proxy = bs_client.account().proxy()?;
+        bs_env
+            .env
+            .set_balance(&proxy, vec![Coin::new(10_000_000, "ujuno")])?;
+
+        let policy = RenewalPolicy {
+            days_before_expiry: 5,
+            duration_days: 30,
+            bytes: None,
+            max_spend: 3_000_000u128.into(),
+        };
+        // the spend of a renewal can't be capped without pricing
+        let err = bs_client
+            .update_config(
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(policy.clone()),
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::RenewalWithoutPricing {}.to_string()
+        );
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(policy.clone()),
+            Some(StoragePricing {
+                funding_denom: "ujuno".to_string(),
+                price_per_gb_month: 1_000_000u128.into(),
+            }),
+        )?;
+
+        // a policy renews for a positive duration, and not before the renewed plan is due
+        for (invalid, reason) in [
+            (
+                RenewalPolicy {
+                    duration_days: 0,
+                    days_before_expiry: 0,
+                    ..policy.clone()
+                },
+                "duration_days must be positive",
+            ),
+            (
+                RenewalPolicy {
+                    days_before_expiry: 31,
+                    ..policy.clone()
+                },
+                "days_before_expiry is longer than duration_days",
+            ),
+        ] {
+            let err = bs_client
+                .update_config(None, None, None, None, None, None, Some(invalid), None)
+                .unwrap_err();
+            assert_eq!(
+                err.root().to_string(),
+                UsbError::InvalidRenewalPolicy(reason.to_string()).to_string()
+            );
+        }
+
+        // nothing to renew without a plan
+        let keeper = bs_env.env.addr_make("keeper");
+        bs_client.call_as(&keeper).tick()?;
+        assert!(bs_client.operations(None, None)?.operations.is_empty());
+
+        // the plan is recorded once the purchase succeeded on jackal
+        bs_client.jackal_msgs(vec![JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days: 30,
+            bytes: 2_000_000_000,
+            payment_denom: String::new(),
+        }])?;
+        assert_eq!(bs_client.storage_plan()?.plan, None);
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        let plan = bs_client.storage_plan()?.plan.unwrap();
+        assert_eq!(plan.bytes, 2_000_000_000);
+        assert_eq!(plan.payment_denom, "ujkl");
+        assert_eq!(
+            plan.expires,
+            bs_env.env.block_info()?.time.plus_seconds(30 * DAY)
+        );
+
+        // not due yet
+        bs_client.call_as(&keeper).tick()?;
+        assert_eq!(bs_client.operations(None, None)?.operations.len(), 1);
+
+        // the active plan is upgraded 5 days before it expires, once
+        bs_env.env.wait_seconds(26 * DAY)?;
+        bs_client.call_as(&keeper).tick()?;
+        bs_client.call_as(&keeper).tick()?;
+        let operations = bs_client.operations(None, None)?.operations;
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[1].kinds, vec![JackalMsgKind::UpgradeStorage]);
+
+        // a failed renewal isn't issued again by the next tick, it is retried
+        dispatch_callback(&bs_env.env, &bs_client, 1, Err("out of gas".to_string()))?;
+        bs_client.call_as(&keeper).tick()?;
+        assert_eq!(bs_client.operations(None, None)?.operations.len(), 2);
+        bs_client.retry_operation(1)?;
+        bs_client.call_as(&keeper).tick()?;
+        assert_eq!(bs_client.operations(None, None)?.operations.len(), 2);
+        dispatch_callback(&bs_env.env, &bs_client, 1, Err("out of gas".to_string()))?;
+
+        // or issued again under a new policy, within its maximum spend
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(RenewalPolicy {
+                max_spend: 1_000_000u128.into(),
+                ..policy.clone()
+            }),
+            None,
+        )?;
+        let err = bs_client.call_as(&keeper).tick().unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::RenewalOverBudget {
+                cost: 2_000_000u128.into(),
+                max_spend: 1_000_000u128.into(),
+            }
+            .to_string()
+        );
+
+        // an expired plan is bought again
+        bs_client.update_config(None, None, None, None, None, None, Some(policy), None)?;
+        bs_env.env.wait_seconds(5 * DAY)?;
+        bs_client.call_as(&keeper).tick()?;
+        let operation = bs_client.operation(2)?;
+        assert_eq!(operation.kinds, vec![JackalMsgKind::BuyStorage]);
+        assert_eq!(operation.status, OperationStatus::Pending);
+        dispatch_callback(&bs_env.env, &bs_client, 2, Ok(executed()))?;
+        assert_eq!(
+            bs_client.storage_plan()?.plan.unwrap().expires,
+            bs_env.env.block_info()?.time.plus_seconds(30 * DAY)
+        );
+
+        Ok(())
+    }
+}
+
+mod sublease {
+    use super::*;
+    use usb::{types::storage::MsgUpgradeStorage, JackalMsg, JackalMsgKind};
+    use usb_plugin::{
+        msg::UsbExecuteMsg,
+        state::{LeaseStatus, OperationStatus, StoragePricing},
+    };
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn leases_buy_storage_for_the_taker() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        // only storage of the plan can be listed
+        let err = bs_client
+            .create_listing(2_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::NoStoragePlan {}.to_string()
+        );
+        bs_client.jackal_msgs(vec![JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days: 90,
+            bytes: 2_000_000_000,
+            payment_denom: String::new(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        let err = bs_client
+            .create_listing(3_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::ListingExceedsPlan {
+                bytes: 3_000_000_000,
+                subleased: 0,
+                plan_bytes: 2_000_000_000,
+            }
+            .to_string()
+        );
+        let err = bs_client
+            .create_listing(2_000_000_000, "ujuno".to_string(), 0, 1_000_000u128.into())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::ListingDuration(0).to_string()
+        );
+        bs_client.create_listing(2_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())?;
+        let err = bs_client
+            .create_listing(1_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::ListingExceedsPlan {
+                bytes: 1_000_000_000,
+                subleased: 2_000_000_000,
+                plan_bytes: 2_000_000_000,
+            }
+            .to_string()
+        );
+
+        let taker = bs_env.env.addr_make("taker");
+        bs_env
+            .env
+            .set_balance(&taker, vec![Coin::new(3_000_000, "ujuno")])?;
+        let take_lease = |bytes: i64, duration_days: i64, for_address: &str, amount: u128| {
+            bs_client.call_as(&taker).execute(
+                &ExecuteMsg::from(UsbExecuteMsg::TakeLease {
+                    listing_id: 0,
+                    bytes,
+                    duration_days,
+                    for_address: for_address.to_string(),
+                }),
+                Some(&[Coin::new(amount, "ujuno")]),
+            )
+        };
+
+        let err = take_lease(1_000_000_000, 30, "juno1taker", 1_000_000).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InvalidJackalAddress("juno1taker".to_string()).to_string()
+        );
+        let err = take_lease(1_000_000_000, 65, "jkl1taker", 2_166_667).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::LeaseDuration {
+                requested: 65,
+                max: 60,
+            }
+            .to_string()
+        );
+        let err = take_lease(1_000_000_000, 30, "jkl1taker", 900_000).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::LeasePayment {
+                expected: Coin::new(1_000_000, "ujuno"),
+                received: 900_000u128.into(),
+            }
+            .to_string()
+        );
+        // jackal doesn't sell storage for less than 30 days
+        let err = take_lease(1_000_000_000, 15, "jkl1taker", 500_000).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::StorageDuration {
+                index: 0,
+                duration_days: 15,
+                min: 30,
+                max: None,
+            }
+            .to_string()
+        );
+
+        // 1 GB for 60 days, bought on jackal for the taker
+        take_lease(1_000_000_000, 60, "jkl1taker", 2_000_000)?;
+        let operation = bs_client.operation(1)?;
+        assert_eq!(operation.kinds, vec![JackalMsgKind::BuyStorage]);
+        assert_eq!(bs_client.lease(0)?.status, LeaseStatus::Pending);
+        assert_eq!(bs_client.listing(0)?.available_bytes, 1_000_000_000);
+        dispatch_callback(&bs_env.env, &bs_client, 1, Ok(executed()))?;
+        assert_eq!(bs_client.lease(0)?.status, LeaseStatus::Active);
+
+        // a second lease for the same address upgrades its storage, keeping the storage of the first:
+        // 2 GB until the first lease ends, 50 days later
+        bs_env.env.wait_seconds(10 * DAY)?;
+        take_lease(1_000_000_000, 30, "jkl1taker", 1_000_000)?;
+        assert_eq!(
+            bs_client.operation(2)?.kinds,
+            vec![JackalMsgKind::UpgradeStorage]
+        );
+        let (_, upgrade) = operation_msg::<MsgUpgradeStorage>(&bs_env.env, &bs_client, 2, 0)?;
+        assert_eq!((upgrade.bytes, upgrade.duration_days), (2_000_000_000, 50));
+        let lease = bs_client.lease(1)?;
+        assert_eq!(lease.upgrades, Some(0));
+        assert_eq!(lease.end, lease.start.plus_days(30));
+        assert_eq!(lease.plan.unwrap().expires, bs_client.lease(0)?.end);
+
+        // the failed purchase can be cancelled, by its taker, for a refund
+        dispatch_callback(&bs_env.env, &bs_client, 2, Err("out of gas".to_string()))?;
+        assert!(matches!(
+            bs_client.operation(2)?.status,
+            OperationStatus::Failed { .. }
+        ));
+        assert_eq!(bs_client.lease(1)?.status, LeaseStatus::Failed);
+        assert_eq!(bs_client.lease(0)?.status, LeaseStatus::Active);
+        let err = bs_client.cancel_lease(0).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::LeaseNotCancellable(0).to_string()
+        );
+        let stranger = bs_env.env.addr_make("stranger");
+        assert!(bs_client.call_as(&stranger).cancel_lease(1).is_err());
+        assert_eq!(bs_env.env.query_balance(&taker, "ujuno")?.u128(), 0);
+        bs_client.call_as(&taker).cancel_lease(1)?;
+        assert_eq!(bs_env.env.query_balance(&taker, "ujuno")?.u128(), 1_000_000);
+        assert_eq!(bs_client.lease(1)?.status, LeaseStatus::Cancelled);
+        let err = bs_client.retry_operation(2).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::LeaseCancelled { id: 2, lease: 1 }.to_string()
+        );
+        assert_eq!(bs_client.listing(0)?.available_bytes, 1_000_000_000);
+
+        // an upgrade outliving the active lease also pays for keeping its storage:
+        // 1 GB for 60 days, and the 1 GB of the first lease for the 10 days past its end
+        bs_env
+            .env
+            .set_balance(&taker, vec![Coin::new(2_333_334, "ujuno")])?;
+        let err = take_lease(1_000_000_000, 60, "jkl1taker", 2_000_000).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::LeasePayment {
+                expected: Coin::new(2_333_334, "ujuno"),
+                received: 2_000_000u128.into(),
+            }
+            .to_string()
+        );
+        take_lease(1_000_000_000, 60, "jkl1taker", 2_333_334)?;
+        let (_, upgrade) = operation_msg::<MsgUpgradeStorage>(&bs_env.env, &bs_client, 3, 0)?;
+        assert_eq!((upgrade.bytes, upgrade.duration_days), (2_000_000_000, 60));
+
+        // leases are listed per taker, and expire with their storage
+        assert_eq!(
+            bs_client
+                .leases(None, None, Some(taker.to_string()))?
+                .leases
+                .len(),
+            3
+        );
+        assert!(bs_client
+            .leases(None, None, Some(stranger.to_string()))?
+            .leases
+            .is_empty());
+        bs_env.env.wait_seconds(50 * DAY)?;
+        assert_eq!(bs_client.lease(0)?.status, LeaseStatus::Expired);
+
+        // a closed listing can't be leased from
+        bs_client.close_listing(0)?;
+        assert!(bs_client.listings(None, None)?.listings.is_empty());
+        assert!(take_lease(1_000_000_000, 30, "jkl1taker", 1_000_000).is_err());
+
+        // its storage is listed again, except for the bytes of the lease that hasn't ended yet
+        let err = bs_client
+            .create_listing(2_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::ListingExceedsPlan {
+                bytes: 2_000_000_000,
+                subleased: 1_000_000_000,
+                plan_bytes: 2_000_000_000,
+            }
+            .to_string()
+        );
+        bs_client.create_listing(1_000_000_000, "ujuno".to_string(), 20, 1_000_000u128.into())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn lease_payments_fund_their_purchase() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        connect_ics20(&interchain, "juno-1", "jackal-1", "jackal")?;
+
+        bs_client.jackal_msgs(vec![JackalMsg::BuyStorage {
+            for_address: String::new(),
+            duration_days: 90,
+            bytes: 2_000_000_000,
+            payment_denom: String::new(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(StoragePricing {
+                funding_denom: "ujuno".to_string(),
+                price_per_gb_month: 1_000_000u128.into(),
+            }),
+        )?;
+        bs_client.create_listing(1_000_000_000, "ujuno".to_string(), 60, 1_000_000u128.into())?;
+
+        // the account holds no funds, the payment reaches it before the storage is paid for
+        let proxy = bs_client.account().proxy()?;
+        assert_eq!(bs_env.env.query_balance(&proxy, "ujuno")?.u128(), 0);
+        let taker = bs_env.env.addr_make("taker");
+        bs_env
+            .env
+            .set_balance(&taker, vec![Coin::new(1_000_000, "ujuno")])?;
+        bs_client.call_as(&taker).execute(
+            &ExecuteMsg::from(UsbExecuteMsg::TakeLease {
+                listing_id: 0,
+                bytes: 1_000_000_000,
+                duration_days: 30,
+                for_address: "jkl1taker".to_string(),
+            }),
+            Some(&[Coin::new(1_000_000, "ujuno")]),
+        )?;
+        assert_eq!(bs_client.operation(1)?.status, OperationStatus::Pending);
+        assert_eq!(bs_env.env.query_balance(&proxy, "ujuno")?.u128(), 0);
+
+        Ok(())
+    }
+}
+
+mod file_index {
+    use super::*;
+    use usb::{
+        helpers::{access_id, hash_and_hex, Access, AccessMap, FilePath},
+        types::filetree::{MsgAddViewers, MsgDeleteFile, MsgRemoveViewers, MsgResetEditors},
+        JackalMsg, JackalMsgKind,
+    };
+
+    /// filetree key of `s/file.txt` owned by the remote proxy of the test account:
+    /// Hex[ hash( "o" + MerklePath + Hex[ hash( remote proxy ) ] ) ]
+    const OWNER_ADDRESS: &str = "f694fb05d4e9b7a48f8bcc4b678d6df43d49a91ebd2d0bf002e7ce731ea858d4";
+
+    #[test]
+    fn succeeded_posts_are_indexed_by_path() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        let home = FilePath::new("s/home")?;
+        let file = FilePath::new("s/home/file.txt")?;
+        let viewers = AccessMap::new().with("jkl1viewer", "wrapped_key");
+        bs_client.upload_file(
+            AccessMap::new(),
+            String::new(),
+            home.clone(),
+            AccessMap::new(),
+            None,
+        )?;
+        bs_client.upload_file(
+            AccessMap::new(),
+            "jklf1fid".to_string(),
+            file.clone(),
+            viewers.clone(),
+            Some(42),
+        )?;
+
+        // indexed once the post succeeded on jackal
+        assert!(bs_client.file(file.clone()).is_err());
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        dispatch_callback(&bs_env.env, &bs_client, 1, Ok(executed()))?;
+        let entry = bs_client.file(file.clone())?;
+        assert_eq!(entry.fid, "jklf1fid");
+        assert_eq!(entry.size, Some(42));
+        assert_eq!(entry.parent, home);
+        assert_eq!(entry.viewers, viewers);
+        assert_eq!(entry.tracking_number.len(), 36);
+
+        // raw posts are indexed too, failed ones aren't
+        bs_client.jackal_msgs(vec![JackalMsg::PostFile {
+            path: FilePath::new("s/home/failed.txt")?,
+            contents: "jklf1failed".to_string(),
+            viewers: AccessMap::new(),
+            editors: AccessMap::new(),
+            tracking_number: "tracking".to_string(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 2, Err("out of gas".to_string()))?;
+        bs_client.jackal_msgs(vec![JackalMsg::PostFile {
+            path: FilePath::new("s/notes.txt")?,
+            contents: "jklf1notes".to_string(),
+            viewers: AccessMap::new(),
+            editors: AccessMap::new(),
+            tracking_number: "tracking".to_string(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 3, Ok(executed()))?;
+
+        let names = |entries: Vec<usb_plugin::state::FileEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.path.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(bs_client.list_dir(FilePath::root(), None, None)?.entries),
+            vec!["s/home", "s/notes.txt"]
+        );
+        assert_eq!(
+            names(
+                bs_client
+                    .list_dir(FilePath::root(), None, Some("home".to_string()))?
+                    .entries
+            ),
+            vec!["s/notes.txt"]
+        );
+        assert_eq!(
+            names(bs_client.list_dir(home.clone(), None, None)?.entries),
+            vec!["s/home/file.txt"]
+        );
+        assert_eq!(
+            names(bs_client.tree(None, None, None)?.entries),
+            vec!["s/home", "s/home/file.txt", "s/notes.txt"]
+        );
+
+        // a post to an indexed path updates its entry
+        bs_env.env.next_block()?;
+        bs_client.upload_file(
+            AccessMap::new(),
+            "jklf1fid2".to_string(),
+            file.clone(),
+            viewers,
+            Some(84),
+        )?;
+        dispatch_callback(&bs_env.env, &bs_client, 4, Ok(executed()))?;
+        let updated = bs_client.file(file.clone())?;
+        assert_eq!(updated.fid, "jklf1fid2");
+        assert_eq!(updated.created_height, entry.created_height);
+        assert!(updated.updated_height > entry.updated_height);
+
+        // deleting a folder drops everything under it
+        bs_client.jackal_msgs(vec![JackalMsg::Delete {
+            path: home.clone(),
+            account: String::new(),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 5, Ok(executed()))?;
+        assert!(bs_client.file(file).is_err());
+        assert_eq!(
+            names(bs_client.tree(None, None, None)?.entries),
+            vec!["s/notes.txt"]
+        );
+        assert!(bs_client.list_dir(home, None, None)?.entries.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn folders_are_made_moved_and_copied_in_one_batch() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let path = |path: &str| FilePath::new(path).unwrap();
+        let paths = |client: &Application<MockBech32, UsbInterface<MockBech32>>| -> Result<_> {
+            Ok(client
+                .tree(None, None, None)?
+                .entries
+                .into_iter()
+                .map(|entry| entry.path.to_string())
+                .collect::<Vec<_>>())
+        };
+
+        // missing parents are only made on request
+        let err = bs_client.make_dir(false, path("s/a/b")).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::MissingParent("s/a".to_string()).to_string()
+        );
+        bs_client.make_dir(true, path("s/a/b"))?;
+        assert_eq!(
+            bs_client.operation(0)?.kinds,
+            vec![JackalMsgKind::PostFile, JackalMsgKind::PostFile]
+        );
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        let err = bs_client.make_dir(true, path("s/a")).unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::PathExists("s/a".to_string()).to_string()
+        );
+
+        let viewers = AccessMap::new().with("jkl1viewer", "wrapped_key");
+        bs_client.upload_file(
+            AccessMap::new(),
+            "jklf1fid".to_string(),
+            path("s/a/b/file.txt"),
+            viewers.clone(),
+            Some(42),
+        )?;
+        dispatch_callback(&bs_env.env, &bs_client, 1, Ok(executed()))?;
+        let file = bs_client.file(path("s/a/b/file.txt"))?;
+
+        // a move reposts every entry under the new parent, then deletes the old ones.
+        // only indexed entries are part of the batch
+        for (from, to, err) in [
+            (
+                "s/a",
+                "s/a/b/c",
+                UsbError::IntoItself {
+                    from: "s/a".to_string(),
+                    to: "s/a/b/c".to_string(),
+                },
+            ),
+            ("s/a/b", "s/a", UsbError::PathExists("s/a".to_string())),
+            ("s/x", "s/y", UsbError::PathNotIndexed("s/x".to_string())),
+            ("s/a", "s/x/y", UsbError::MissingParent("s/x".to_string())),
+        ] {
+            let result = bs_client.move_path(path(from), path(to)).unwrap_err();
+            assert_eq!(result.root().to_string(), err.to_string());
+        }
+        bs_client.move_path(path("s/a"), path("s/c"))?;
+        let operation = bs_client.operation(2)?;
+        assert_eq!(
+            operation.kinds,
+            [
+                vec![JackalMsgKind::PostFile; 3],
+                vec![JackalMsgKind::Delete; 3]
+            ]
+            .concat()
+        );
+        assert_eq!(paths(&bs_client)?, vec!["s/a", "s/a/b", "s/a/b/file.txt"]);
+        dispatch_callback(&bs_env.env, &bs_client, 2, Ok(executed()))?;
+        assert_eq!(paths(&bs_client)?, vec!["s/c", "s/c/b", "s/c/b/file.txt"]);
+        let moved = bs_client.file(path("s/c/b/file.txt"))?;
+        assert_eq!(moved.fid, file.fid);
+        assert_eq!(moved.tracking_number, file.tracking_number);
+        assert_eq!(moved.size, Some(42));
+        assert_eq!(moved.viewers, viewers);
+        assert_eq!(moved.parent, path("s/c/b"));
+
+        // a copy only posts
+        bs_client.copy(path("s/c/b"), path("s/d"))?;
+        assert_eq!(
+            bs_client.operation(3)?.kinds,
+            vec![JackalMsgKind::PostFile; 2]
+        );
+        dispatch_callback(&bs_env.env, &bs_client, 3, Ok(executed()))?;
+        assert_eq!(
+            paths(&bs_client)?,
+            vec!["s/c", "s/c/b", "s/c/b/file.txt", "s/d", "s/d/file.txt"]
+        );
+        assert_eq!(
+            bs_client.file(path("s/d/file.txt"))?.tracking_number,
+            file.tracking_number
+        );
+
+        // v4 has no MsgDangerDelete, every entry is deleted with MsgDeleteFile
+        bs_client.update_config(
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(ProtocolVersion::V4),
+            None,
+            None,
+        )?;
+        bs_client.move_path(path("s/d"), path("s/e"))?;
+        assert_eq!(
+            bs_client.operation(4)?.kinds,
+            [
+                vec![JackalMsgKind::PostFile; 2],
+                vec![JackalMsgKind::DeleteFile; 2]
+            ]
+            .concat()
+        );
+        for (index, deleted) in [(2, "s/d/file.txt"), (3, "s/d")] {
+            let (type_url, msg) =
+                operation_msg::<MsgDeleteFile>(&bs_env.env, &bs_client, 4, index)?;
+            assert_eq!(type_url, "/canine_chain.filetree.MsgDeleteFile");
+            assert_eq!(msg.hash_path, path(deleted).merkle_path());
+            assert_eq!(msg.account, hash_and_hex(&msg.creator));
+        }
+        dispatch_callback(&bs_env.env, &bs_client, 4, Ok(executed()))?;
+        assert_eq!(
+            paths(&bs_client)?,
+            vec!["s/c", "s/c/b", "s/c/b/file.txt", "s/e", "s/e/file.txt"]
+        );
+
+        // the tree is paged by path, a folder comes before its entries
+        let page = |under: Option<&str>, start_after: Option<&str>| -> Result<Vec<String>> {
+            Ok(bs_client
+                .tree(Some(2), under.map(path), start_after.map(path))?
+                .entries
+                .into_iter()
+                .map(|entry| entry.path.to_string())
+                .collect())
+        };
+        assert_eq!(page(None, None)?, vec!["s/c", "s/c/b"]);
+        assert_eq!(page(None, Some("s/c/b"))?, vec!["s/c/b/file.txt", "s/e"]);
+        assert_eq!(page(None, Some("s/e"))?, vec!["s/e/file.txt"]);
+        assert_eq!(page(Some("s/c"), None)?, vec!["s/c", "s/c/b"]);
+        assert_eq!(page(Some("s/c"), Some("s/c/b"))?, vec!["s/c/b/file.txt"]);
+        assert!(page(Some("s/c"), Some("s/c/b/file.txt"))?.is_empty());
+
+        // a folder handed over to another owner leaves the index, with everything under it
+        bs_client.jackal_msgs(vec![JackalMsg::ChangeOwner {
+            address: path("s/e").merkle_path(),
+            owner: String::new(),
+            new_owner: hash_and_hex("jkl1friend"),
+        }])?;
+        dispatch_callback(&bs_env.env, &bs_client, 5, Ok(executed()))?;
+        assert_eq!(paths(&bs_client)?, vec!["s/c", "s/c/b", "s/c/b/file.txt"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn files_are_shared_and_revoked_by_recipient() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let file = FilePath::new("s/file.txt")?;
+        let recipients = |client: &Application<MockBech32, UsbInterface<MockBech32>>| -> Result<_> {
+            Ok(client
+                .shares(file.clone())?
+                .shares
+                .into_iter()
+                .map(|share| share.recipient)
+                .collect::<Vec<_>>())
+        };
+
+        let err = bs_client
+            .share_file(file.clone(), "jkl1friend".to_string(), "key".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::PathNotIndexed("s/file.txt".to_string()).to_string()
+        );
+        bs_client.upload_file(
+            AccessMap::new(),
+            "jklf1fid".to_string(),
+            file.clone(),
+            AccessMap::new().with("jkl1owner", "owner_key"),
+            None,
+        )?;
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+        let tracking_number = bs_client.file(file.clone())?.tracking_number;
+
+        // only jackal addresses can be viewers
+        let err = bs_client
+            .share_file(file.clone(), "juno1friend".to_string(), "key".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::InvalidJackalAddress("juno1friend".to_string()).to_string()
+        );
+
+        // the share is recorded once it went through on jackal
+        bs_client.share_file(
+            file.clone(),
+            "jkl1friend".to_string(),
+            "friend_key".to_string(),
+        )?;
+        assert_eq!(
+            bs_client.operation(1)?.kinds,
+            vec![JackalMsgKind::AddViewers]
+        );
+        // viewer changes address the file by the owner address x/filetree stores it under
+        let (type_url, msg) = operation_msg::<MsgAddViewers>(&bs_env.env, &bs_client, 1, 0)?;
+        assert_eq!(type_url, "/canine_chain.filetree.MsgAddViewers");
+        assert_eq!(msg.address, file.merkle_path());
+        assert_eq!(msg.file_owner, OWNER_ADDRESS);
+        assert_eq!(recipients(&bs_client)?, vec!["jkl1owner"]);
+        dispatch_callback(&bs_env.env, &bs_client, 1, Ok(executed()))?;
+        let shares = bs_client.shares(file.clone())?.shares;
+        assert_eq!(shares[0].recipient, "jkl1friend");
+        assert_eq!(
+            shares[0].viewer_id,
+            access_id(Access::Viewer, &tracking_number, "jkl1friend")
+        );
+        assert_eq!(
+            bs_client.file(file.clone())?.viewers.0["jkl1friend"],
+            "friend_key"
+        );
+
+        // revoking needs only the recipient
+        let err = bs_client
+            .revoke_share(file.clone(), "jkl1stranger".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::NotShared {
+                path: "s/file.txt".to_string(),
+                recipient: "jkl1stranger".to_string(),
+            }
+            .to_string()
+        );
+        bs_client.revoke_share(file.clone(), "jkl1friend".to_string())?;
+        assert_eq!(
+            bs_client.operation(2)?.kinds,
+            vec![JackalMsgKind::DeleteViewers]
+        );
+        let (_, msg) = operation_msg::<MsgRemoveViewers>(&bs_env.env, &bs_client, 2, 0)?;
+        assert_eq!(msg.file_owner, OWNER_ADDRESS);
+        dispatch_callback(&bs_env.env, &bs_client, 2, Ok(executed()))?;
+        assert_eq!(recipients(&bs_client)?, vec!["jkl1owner"]);
+
+        // editor and ownership changes default to the same owner address
+        bs_client.jackal_msgs(vec![JackalMsg::ResetEditors {
+            address: file.merkle_path(),
+            owner: String::new(),
+        }])?;
+        let (_, msg) = operation_msg::<MsgResetEditors>(&bs_env.env, &bs_client, 3, 0)?;
+        assert_eq!(msg.file_owner, OWNER_ADDRESS);
+
+        Ok(())
+    }
+}
+
+mod public_keys {
+    use super::*;
+    use usb::JackalMsg;
+    use usb_adapter::{msg::UsbAdapterInstantiateMsg, UsbInterface as UsbAdapterInterface};
+
+    #[test]
+    fn posted_keys_are_cached_and_registered() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        // the usb-adapter keeps the keys of every account
+        bs_env
+            .abs
+            .publisher_builder(Namespace::new(usb_adapter::USB_NAMESPACE)?)
+            .build()?
+            .publish_adapter::<_, UsbAdapterInterface<_>>(UsbAdapterInstantiateMsg {})?;
+        let bs_client = bs_env.client2;
+        let other = bs_env.client1;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+        let account = bs_client.account();
+        account.install_adapter::<UsbAdapterInterface<_>>(&[])?;
+        account
+            .as_ref()
+            .manager
+            .update_adapter_authorized_addresses(
+                usb_adapter::USB_ID,
+                vec![bs_client.address()?.to_string()],
+                vec![],
+            )?;
+        let account_id = account.id()?;
+
+        bs_client.jackal_msgs(vec![JackalMsg::PostKey {
+            key: "02abcdef".to_string(),
+        }])?;
+        assert_eq!(bs_client.public_key(account_id.clone())?.key, None);
+        dispatch_callback(&bs_env.env, &bs_client, 0, Ok(executed()))?;
+
+        // cached by the app, and looked up by other accounts in the adapter
+        assert_eq!(
+            bs_client.public_key(account_id.clone())?.key,
+            Some("02abcdef".to_string())
+        );
+        assert_eq!(
+            other.public_key(account_id)?.key,
+            Some("02abcdef".to_string())
+        );
+        assert_eq!(other.public_key(other.account().id()?)?.key, None);
+
+        Ok(())
+    }
+}
+
+mod remote_queries {
+    use super::*;
+    use polytone::callbacks::ErrorResponse;
+    use prost::Message;
+    use usb::{
+        helpers::FilePath,
+        types::filetree::{Pubkey, QueryPubkeyResponse},
+        JackalQuery, JackalQueryResponse,
+    };
+    use usb_plugin::state::OperationStatus;
+
+    #[test]
+    fn jackal_is_queried_over_ibc() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        // an empty address is the jackal address of the account
+        bs_client.query_jackal(JackalQuery::PubKey {
+            address: String::new(),
+        })?;
+        let pending = bs_client.remote_query(0)?;
+        assert_eq!(pending.status, OperationStatus::Pending);
+        let JackalQuery::PubKey { address } = pending.query.clone() else {
+            panic!("expected a pubkey query, got {:?}", pending.query);
+        };
+        assert!(address.starts_with("jkl1"));
+
+        let data = QueryPubkeyResponse {
+            pubkey: Some(Pubkey {
+                address: address.clone(),
+                key: "02ab".to_string(),
+            }),
+        }
+        .encode_to_vec();
+        query_callback(&bs_env.env, &bs_client, 0, Ok(vec![data.into()]))?;
+        let answered = bs_client.remote_query(0)?;
+        assert_eq!(answered.status, OperationStatus::Succeeded);
+        assert_eq!(
+            answered.response,
+            Some(JackalQueryResponse::PubKey {
+                address: address.clone(),
+                key: "02ab".to_string(),
+            })
+        );
+        assert_eq!(bs_client.latest_answer(pending.query)?.id, 0);
+
+        // failed and timed out queries keep the previous answer
+        let file = JackalQuery::File {
+            path: FilePath::new("s/home/file.txt")?,
+            owner: address.clone(),
+        };
+        bs_client.query_jackal(file.clone())?;
+        bs_client.query_jackal(JackalQuery::ActiveDeals {
+            cid: "jklc1cid".to_string(),
+        })?;
+        query_callback(
+            &bs_env.env,
+            &bs_client,
+            1,
+            Err(ErrorResponse {
+                message_index: 0u64.into(),
+                error: "file not found".to_string(),
+            }),
+        )?;
+        query_callback(
+            &bs_env.env,
+            &bs_client,
+            2,
+            Err(ErrorResponse {
+                message_index: 0u64.into(),
+                error: "timeout".to_string(),
+            }),
+        )?;
+        assert_eq!(
+            bs_client.remote_query(1)?.status,
+            OperationStatus::Failed {
+                error: "file not found".to_string()
+            }
+        );
+        assert_eq!(bs_client.remote_query(2)?.status, OperationStatus::TimedOut);
+        assert!(bs_client.latest_answer(file).is_err());
+        assert_eq!(bs_client.remote_queries(None, None)?.queries.len(), 3);
+
+        // only callers allowed to send msgs can query
+        let stranger = bs_env.env.addr_make("stranger");
+        let err = bs_client
+            .call_as(&stranger)
+            .query_jackal(JackalQuery::StoragePaymentInfo {
+                address: String::new(),
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::Unauthorized {
+                sender: stranger.to_string(),
+            }
+            .to_string()
+        );
+
+        Ok(())
+    }
+}
+
+mod authorization {
+    use super::*;
+    use usb::{JackalMsg, JackalMsgKind, JackalQuery};
+    use usb_plugin::state::{Authorization, Caller};
+
+    #[test]
+    fn allowlist_restricts_callers_and_kinds() -> Result<()> {
+        let (interchain, bs_env, _) = ibc_test_env()?;
+
+        let bs_client = bs_env.client2;
+        create_remote_account(&interchain, "juno-1", &bs_client, "jackal")?;
+
+        let post_key = || {
+            vec![JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            }]
+        };
+        let stranger = bs_env.env.addr_make("stranger");
+
+        // unknown callers are rejected
+        let err = bs_client
+            .call_as(&stranger)
+            .jackal_msgs(post_key())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::Unauthorized {
+                sender: stranger.to_string()
+            }
+            .to_string()
+        );
+
+        // a module that is not installed on the account doesn't authorize anyone
+        bs_client.update_config(
+            Some(vec![
+                Authorization {
+                    caller: Caller::Address(stranger.to_string()),
+                    kinds: Some(vec![JackalMsgKind::PostKey]),
+                    queries: false,
+                },
+                Authorization {
+                    caller: Caller::Module("abstract:not-installed".to_string()),
+                    kinds: None,
+                    queries: false,
+                },
+            ]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        assert_eq!(bs_client.config()?.authorized.len(), 2);
+
+        // allowed kinds are dispatched, others rejected
+        bs_client.call_as(&stranger).jackal_msgs(post_key())?;
+        let err = bs_client
+            .call_as(&stranger)
+            .jackal_msgs(vec![JackalMsg::CancelContract {
+                cid: "test".to_string(),
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::UnauthorizedMsg {
+                sender: stranger.to_string(),
+                kind: JackalMsgKind::CancelContract,
+            }
+            .to_string()
+        );
+
+        // the owner is always allowed
+        bs_client.jackal_msgs(vec![JackalMsg::CancelContract {
+            cid: "test".to_string(),
+        }])?;
+
+        // queries of jackal need their own authorization
+        let pub_key = || JackalQuery::PubKey {
+            address: String::new(),
+        };
+        let err = bs_client
+            .call_as(&stranger)
+            .query_jackal(pub_key())
+            .unwrap_err();
+        assert_eq!(
+            err.root().to_string(),
+            UsbError::UnauthorizedQuery {
+                sender: stranger.to_string(),
+            }
+            .to_string()
+        );
+        bs_client.update_config(
+            Some(vec![Authorization {
+                caller: Caller::Address(stranger.to_string()),
+                kinds: Some(vec![JackalMsgKind::PostKey]),
+                queries: true,
+            }]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        bs_client.call_as(&stranger).query_jackal(pub_key())?;
+
         Ok(())
     }
 }
 
+/// Register a remote account with the app installed for the account owning `app` and relay the ibc packets
+pub fn create_remote_account<Chain: IbcQueryHandler, IBC: InterchainEnv<Chain>>(
+    interchain: &IBC,
+    origin_chain_id: &str,
+    app: &Application<Chain, UsbInterface<Chain>>,
+    host_chain: &str,
+) -> Result<()> {
+    let account = app.account();
+    account.set_ibc_status(true)?;
+    // the app on the remote account broadcasts the msgs dispatched by `app`
+    let tx = account.as_ref().manager.exec_on_module(
+        to_json_binary(&proxy::ExecuteMsg::IbcAction {
+            msg: ibc_client::ExecuteMsg::Register {
+                host_chain: host_chain.to_string(),
+                base_asset: None,
+                namespace: None,
+                install_modules: vec![ModuleInstallConfig::new(
+                    ModuleInfo::from_id_latest(USB_ID)?,
+                    Some(to_json_binary(&instantiate_msg(host_chain))?),
+                )],
+            },
+        })?,
+        PROXY.to_string(),
+        &[],
+    )?;
+    interchain.check_ibc(origin_chain_id, tx)?.into_result()?;
+
+    Ok(())
+}
+
+/// Create an ics20 channel between the chains and register it in the ans of the origin chain
+pub fn connect_ics20<Chain: IbcQueryHandler, IBC: InterchainEnv<Chain>>(
+    interchain: &IBC,
+    origin_chain_id: &str,
+    remote_chain_id: &str,
+    host_chain: &str,
+) -> Result<()> {
+    let channel = interchain
+        .create_channel(
+            origin_chain_id,
+            remote_chain_id,
+            &PortId::transfer(),
+            &PortId::transfer(),
+            "ics20-1",
+            None,
+        )?
+        .interchain_channel
+        .get_ordered_ports_from(origin_chain_id)?
+        .0
+        .channel
+        .unwrap();
+    Abstract::load_from(interchain.chain(origin_chain_id)?)?
+        .ans_host
+        .update_channels(
+            vec![(
+                UncheckedChannelEntry {
+                    connected_chain: host_chain.to_string(),
+                    protocol: ICS20.to_string(),
+                },
+                channel.to_string(),
+            )],
+            vec![],
+        )?;
+
+    Ok(())
+}
+
+/// Call the dispatch callback of `app` as its ibc-client, as if operation `id` came back with `result`
+pub fn dispatch_callback(
+    chain: &MockBech32,
+    app: &Application<MockBech32, UsbInterface<MockBech32>>,
+    id: u64,
+    result: Result<ExecutionResponse, String>,
+) -> Result<()> {
+    let ibc_client = Abstract::load_from(chain.clone())?.ibc.client.address()?;
+    app.call_as(&ibc_client).execute(
+        &ExecuteMsg::IbcCallback(IbcResponseMsg {
+            id: "dispatch".to_string(),
+            msg: Some(to_json_binary(&id)?),
+            result: CallbackResult::Execute {
+                initiator_msg: Binary::default(),
+                result,
+            },
+        }),
+        None,
+    )?;
+
+    Ok(())
+}
+
+pub fn query_callback(
+    chain: &MockBech32,
+    app: &Application<MockBech32, UsbInterface<MockBech32>>,
+    id: u64,
+    result: Result<Vec<Binary>, polytone::callbacks::ErrorResponse>,
+) -> Result<()> {
+    let ibc_client = Abstract::load_from(chain.clone())?.ibc.client.address()?;
+    app.call_as(&ibc_client).execute(
+        &ExecuteMsg::IbcCallback(IbcResponseMsg {
+            id: "query".to_string(),
+            msg: Some(to_json_binary(&id)?),
+            result: CallbackResult::Query {
+                query: cosmwasm_std::QueryRequest::Stargate {
+                    path: String::new(),
+                    data: Binary::default(),
+                },
+                result,
+            },
+        }),
+        None,
+    )?;
+
+    Ok(())
+}
+
+/// Decode the stargate msg at `index` of the batch recorded as operation `id`
+pub fn operation_msg<M: prost::Message + Default>(
+    chain: &MockBech32,
+    app: &Application<MockBech32, UsbInterface<MockBech32>>,
+    id: u64,
+    index: usize,
+) -> Result<(String, M)> {
+    let raw = chain
+        .wasm_querier()
+        .raw_query(app.address()?, OPERATION_MSGS.key(id).to_vec())?;
+    let msgs: Vec<cosmwasm_std::CosmosMsg> = cosmwasm_std::from_json(raw)?;
+    match &msgs[index] {
+        cosmwasm_std::CosmosMsg::Stargate { type_url, value } => {
+            Ok((type_url.clone(), M::decode(value.as_slice())?))
+        }
+        msg => panic!("expected a stargate msg, got {msg:?}"),
+    }
+}
+
 pub fn ibc_connect_polytone_and_abstract<Chain: IbcQueryHandler, IBC: InterchainEnv<Chain>>(
     interchain: &IBC,
     origin_chain_id: &str,
@@ -178,8 +2255,7 @@ pub fn ibc_connect_polytone_and_abstract<Chain: IbcQueryHandler, IBC: Interchain
         None, // Unordered channel
     )?;
     // Create the connection between client and host
-    let addr =
-        abstract_ibc_connection_with(&abstr_origin, interchain, &abstr_remote, &origin_polytone)?;
+    abstract_ibc_connection_with(&abstr_origin, interchain, &abstr_remote, &origin_polytone)?;
 
     Ok(())
 }
@@ -191,10 +2267,10 @@ pub fn abstract_ibc_connection_with<Chain: IbcQueryHandler, IBC: InterchainEnv<C
     polytone_src: &Polytone<Chain>,
 ) -> Result<(), InterchainError> {
     // First we register client and host respectively
-    let chain1_id = abstr.ibc.client.get_chain().chain_id();
+    let chain1_id = cw_orch::environment::Environment::environment(&abstr.ibc.client).chain_id();
     let chain1_name = ChainName::from_chain_id(&chain1_id);
 
-    let chain2_id = dest.ibc.client.get_chain().chain_id();
+    let chain2_id = cw_orch::environment::Environment::environment(&dest.ibc.client).chain_id();
     let chain2_name = ChainName::from_chain_id(&chain2_id);
 
     // First, we register the host with the client.
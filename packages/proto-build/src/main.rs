@@ -0,0 +1,54 @@
+//! # usb-proto-build
+//!
+//! Compiles the canine-chain protos in `packages/usb/proto` into the prost types
+//! checked in at `packages/usb/src/types/proto`. Uses `protox`, so no `protoc` install is needed.
+//!
+//! The protos are hand-written, not vendored yet, see `packages/usb/proto/README.md`.
+
+use std::path::PathBuf;
+
+/// canine-chain release the protos are vendored from, `None` while they are hand-written
+const CANINE_CHAIN_TAG: Option<&str> = None;
+
+/// canine-chain modules whose `tx.proto` is compiled
+const MODULES: [&str; 4] = ["filetree", "storage", "rns", "notifications"];
+/// modules whose `query.proto` is compiled too, for the queries sent to jackal over ibc
+const QUERY_MODULES: [&str; 2] = ["filetree", "storage"];
+
+fn main() -> anyhow::Result<()> {
+    let usb_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../usb")
+        .canonicalize()?;
+    let proto_dir = usb_dir.join("proto");
+    let out_dir = usb_dir.join("src/types/proto");
+
+    let files: Vec<PathBuf> = MODULES
+        .iter()
+        .map(|module| proto_dir.join(format!("canine_chain/{module}/tx.proto")))
+        .chain(
+            QUERY_MODULES
+                .iter()
+                .map(|module| proto_dir.join(format!("canine_chain/{module}/query.proto"))),
+        )
+        .collect();
+    let file_descriptors = protox::compile(&files, [&proto_dir])?;
+
+    std::fs::create_dir_all(&out_dir)?;
+    prost_build::Config::new()
+        .out_dir(&out_dir)
+        .compile_fds(file_descriptors)?;
+
+    match CANINE_CHAIN_TAG {
+        Some(tag) => println!("compiled canine-chain {tag} protos"),
+        None => {
+            println!("warning: compiled hand-written canine-chain protos, not pinned to a release")
+        }
+    }
+    for module in MODULES {
+        println!(
+            "generated {}",
+            out_dir.join(format!("canine_chain.{module}.rs")).display()
+        );
+    }
+    Ok(())
+}
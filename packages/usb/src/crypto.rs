@@ -0,0 +1,211 @@
+//! # crypto
+//!
+//! Off-chain implementation of Jackal's file encryption, compatible with the Jackal JS client:
+//! 1. a random AES-256-GCM key & 16 byte IV is generated for each file
+//! 2. the file details and contents are encrypted with it, in 32MiB chunks
+//! 3. aesIV + aesKey is wrapped via ECIES for the secp256k1 public key of each viewer & editor,
+//!    the same key that is published via `PostKey`. The hex encoded result is the value of an [`AccessMap`](crate::helpers::AccessMap).
+//!
+//! An encrypted file is a sequence of segments, each made of the 8 digit, zero padded length of the
+//! encrypted chunk followed by the chunk's `ciphertext + tag`. The first segment holds the json [`FileDetails`].
+
+use aes_gcm::{
+    aead::{consts::U16, Aead, KeyInit},
+    aes::Aes256,
+    AesGcm,
+};
+use cosmwasm_schema::serde::{Deserialize, Serialize};
+use cosmwasm_std::{from_json, to_json_vec};
+use rand_core::{OsRng, RngCore};
+
+use crate::error::CryptoError;
+
+/// AES-256-GCM with the 16 byte IV used by Jackal
+type Aes256Gcm16 = AesGcm<Aes256, U16>;
+
+/// size of the plaintext chunks a file is encrypted in
+pub const CHUNK_SIZE: usize = 32 * 1024 * 1024;
+/// length of the ascii encoded size prefixing each encrypted chunk
+const SEGMENT_HEADER: usize = 8;
+/// aesIV length
+pub const IV_LENGTH: usize = 16;
+/// aesKey length
+pub const KEY_LENGTH: usize = 32;
+
+/// Symmetric key of a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileKey {
+    pub key: [u8; KEY_LENGTH],
+    pub iv: [u8; IV_LENGTH],
+}
+
+impl FileKey {
+    /// generate a random key and IV
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LENGTH];
+        let mut iv = [0u8; IV_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        OsRng.fill_bytes(&mut iv);
+        Self { key, iv }
+    }
+
+    /// aesIV + aesKey
+    pub fn to_bytes(&self) -> [u8; IV_LENGTH + KEY_LENGTH] {
+        let mut bytes = [0u8; IV_LENGTH + KEY_LENGTH];
+        bytes[..IV_LENGTH].copy_from_slice(&self.iv);
+        bytes[IV_LENGTH..].copy_from_slice(&self.key);
+        bytes
+    }
+
+    /// parse aesIV + aesKey
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != IV_LENGTH + KEY_LENGTH {
+            return Err(CryptoError::InvalidLength {
+                expected: IV_LENGTH + KEY_LENGTH,
+                actual: bytes.len(),
+            });
+        }
+        let mut key = Self {
+            key: [0u8; KEY_LENGTH],
+            iv: [0u8; IV_LENGTH],
+        };
+        key.iv.copy_from_slice(&bytes[..IV_LENGTH]);
+        key.key.copy_from_slice(&bytes[IV_LENGTH..]);
+        Ok(key)
+    }
+
+    /// AES-256-GCM encrypt `data`, returning `ciphertext + tag`
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Aes256Gcm16::new(&self.key.into())
+            .encrypt(&self.iv.into(), data)
+            .map_err(|_| CryptoError::Encryption)
+    }
+
+    /// AES-256-GCM decrypt `ciphertext + tag`
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Aes256Gcm16::new(&self.key.into())
+            .decrypt(&self.iv.into(), data)
+            .map_err(|_| CryptoError::Decryption)
+    }
+
+    /// wrap the key for the hex encoded secp256k1 `public_key`, returns the hex encoded ECIES ciphertext
+    pub fn wrap_for(&self, public_key: &str) -> Result<String, CryptoError> {
+        let public_key = hex::decode(public_key)?;
+        ecies_encrypt(&public_key, &self.to_bytes()).map(hex::encode)
+    }
+
+    /// unwrap a hex encoded key wrapped for the public key of `secret_key`
+    pub fn unwrap_with(wrapped: &str, secret_key: &[u8]) -> Result<Self, CryptoError> {
+        let wrapped = hex::decode(wrapped)?;
+        Self::from_bytes(&ecies_decrypt(secret_key, &wrapped)?)
+    }
+}
+
+/// Details of a file, stored encrypted in front of its contents
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "cosmwasm_schema::serde", rename_all = "camelCase")]
+pub struct FileDetails {
+    pub name: String,
+    /// last modification time, in milliseconds since the unix epoch
+    pub last_modified: u64,
+    /// mime type, ex: `text/plain`
+    #[serde(rename = "type")]
+    pub file_type: String,
+    /// size of the plaintext contents in bytes
+    pub size: u64,
+}
+
+/// encrypt a file in Jackal's format
+pub fn encrypt_file(
+    key: &FileKey,
+    details: &FileDetails,
+    contents: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let mut encrypted = Vec::with_capacity(contents.len() + 1024);
+    push_segment(&mut encrypted, key, &to_json_vec(details)?)?;
+    for chunk in contents.chunks(CHUNK_SIZE) {
+        push_segment(&mut encrypted, key, chunk)?;
+    }
+    Ok(encrypted)
+}
+
+/// decrypt a file in Jackal's format, returning its details and contents
+pub fn decrypt_file(
+    key: &FileKey,
+    encrypted: &[u8],
+) -> Result<(FileDetails, Vec<u8>), CryptoError> {
+    let mut segments = Segments(encrypted);
+    let details = segments
+        .next()
+        .ok_or_else(|| CryptoError::MalformedFile("missing file details".to_string()))??;
+    let details: FileDetails = from_json(key.decrypt(details)?)?;
+
+    // the size comes from the file itself, the contents can't be longer than the encrypted file
+    let capacity = usize::try_from(details.size)
+        .unwrap_or(usize::MAX)
+        .min(encrypted.len());
+    let mut contents = Vec::with_capacity(capacity);
+    for segment in segments {
+        contents.extend(key.decrypt(segment?)?);
+    }
+    Ok((details, contents))
+}
+
+/// ECIES encrypt `msg` for the secp256k1 `public_key`, compressed or not
+pub fn ecies_encrypt(public_key: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ecies::encrypt(public_key, msg).map_err(|e| CryptoError::Ecies(e.to_string()))
+}
+
+/// ECIES decrypt `msg` with the secp256k1 `secret_key`
+pub fn ecies_decrypt(secret_key: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ecies::decrypt(secret_key, msg).map_err(|e| CryptoError::Ecies(e.to_string()))
+}
+
+/// hex encoded, compressed secp256k1 public key of `secret_key`, as published via `PostKey`
+pub fn public_key(secret_key: &[u8]) -> Result<String, CryptoError> {
+    let secret_key =
+        ecies::SecretKey::parse_slice(secret_key).map_err(|e| CryptoError::Ecies(e.to_string()))?;
+    Ok(hex::encode(
+        ecies::PublicKey::from_secret_key(&secret_key).serialize_compressed(),
+    ))
+}
+
+fn push_segment(out: &mut Vec<u8>, key: &FileKey, chunk: &[u8]) -> Result<(), CryptoError> {
+    let encrypted = key.encrypt(chunk)?;
+    out.extend(format!("{:0width$}", encrypted.len(), width = SEGMENT_HEADER).as_bytes());
+    out.extend(encrypted);
+    Ok(())
+}
+
+/// iterator over the encrypted chunks of a file
+struct Segments<'a>(&'a [u8]);
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Result<&'a [u8], CryptoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let segment = (|| {
+            let header = self.0.get(..SEGMENT_HEADER).ok_or_else(|| {
+                CryptoError::MalformedFile("truncated segment header".to_string())
+            })?;
+            let len: usize = std::str::from_utf8(header)
+                .ok()
+                .and_then(|header| header.parse().ok())
+                .ok_or_else(|| CryptoError::MalformedFile("invalid segment header".to_string()))?;
+            let segment = self
+                .0
+                .get(SEGMENT_HEADER..SEGMENT_HEADER + len)
+                .ok_or_else(|| CryptoError::MalformedFile("truncated segment".to_string()))?;
+            self.0 = &self.0[SEGMENT_HEADER + len..];
+            Ok(segment)
+        })();
+        if segment.is_err() {
+            // stop iterating after an error
+            self.0 = &[];
+        }
+        Some(segment)
+    }
+}
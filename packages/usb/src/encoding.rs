@@ -0,0 +1,113 @@
+//! # encoding
+//!
+//! Turns a [`JackalMsg`] into the canine-chain protobuf msg it represents on a given protocol version,
+//! wrapped in a `CosmosMsg::Stargate` ready to be broadcast on jackal.
+
+use std::fmt;
+
+use cosmwasm_std::{Binary, CosmosMsg};
+use prost::Message;
+
+use crate::{
+    error::JackalError,
+    types::filetree::MsgPostFileResponse,
+    versions::{v3, v4, ProtocolVersion},
+    JackalMsg, JackalMsgKind, JackalMsgResponse,
+};
+
+impl JackalMsg {
+    /// Type url of the canine-chain msg this variant is encoded to on `version`
+    pub fn type_url(&self, version: ProtocolVersion) -> Result<&'static str, JackalError> {
+        match version {
+            ProtocolVersion::V3 => Ok(v3::type_url(self)),
+            ProtocolVersion::V4 => v4::type_url(self),
+        }
+    }
+
+    /// Encode into a stargate msg of `version`, signed by `creator`, the jackal address broadcasting the msg.
+    /// Errors if the msg has no counterpart on `version`, or posts a file at the root.
+    pub fn into_cosmos_msg(
+        self,
+        version: ProtocolVersion,
+        creator: &str,
+    ) -> Result<CosmosMsg, JackalError> {
+        match version {
+            ProtocolVersion::V3 => v3::encode(self, creator),
+            ProtocolVersion::V4 => v4::encode(self, creator),
+        }
+    }
+
+    /// Kind of the variant, ex: [`JackalMsgKind::PostFile`]
+    pub fn kind(&self) -> JackalMsgKind {
+        match self {
+            JackalMsg::AddViewers { .. } => JackalMsgKind::AddViewers,
+            JackalMsg::BuyStorage { .. } => JackalMsgKind::BuyStorage,
+            JackalMsg::UpgradeStorage { .. } => JackalMsgKind::UpgradeStorage,
+            JackalMsg::CancelContract { .. } => JackalMsgKind::CancelContract,
+            JackalMsg::Delete { .. } => JackalMsgKind::Delete,
+            JackalMsg::MakeRoot { .. } => JackalMsgKind::MakeRoot,
+            JackalMsg::PostFile { .. } => JackalMsgKind::PostFile,
+            JackalMsg::PostKey { .. } => JackalMsgKind::PostKey,
+            JackalMsg::DeleteViewers { .. } => JackalMsgKind::DeleteViewers,
+            JackalMsg::SignContract { .. } => JackalMsgKind::SignContract,
+            JackalMsg::AddEditors { .. } => JackalMsgKind::AddEditors,
+            JackalMsg::RemoveEditors { .. } => JackalMsgKind::RemoveEditors,
+            JackalMsg::ResetEditors { .. } => JackalMsgKind::ResetEditors,
+            JackalMsg::ResetViewers { .. } => JackalMsgKind::ResetViewers,
+            JackalMsg::ChangeOwner { .. } => JackalMsgKind::ChangeOwner,
+            JackalMsg::DeleteFile { .. } => JackalMsgKind::DeleteFile,
+            JackalMsg::ProvisionFileTree { .. } => JackalMsgKind::ProvisionFileTree,
+        }
+    }
+}
+
+impl JackalMsgKind {
+    /// snake_case name of the kind, ex: `post_file`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JackalMsgKind::AddViewers => "add_viewers",
+            JackalMsgKind::BuyStorage => "buy_storage",
+            JackalMsgKind::UpgradeStorage => "upgrade_storage",
+            JackalMsgKind::CancelContract => "cancel_contract",
+            JackalMsgKind::Delete => "delete",
+            JackalMsgKind::MakeRoot => "make_root",
+            JackalMsgKind::PostFile => "post_file",
+            JackalMsgKind::PostKey => "post_key",
+            JackalMsgKind::DeleteViewers => "delete_viewers",
+            JackalMsgKind::SignContract => "sign_contract",
+            JackalMsgKind::AddEditors => "add_editors",
+            JackalMsgKind::RemoveEditors => "remove_editors",
+            JackalMsgKind::ResetEditors => "reset_editors",
+            JackalMsgKind::ResetViewers => "reset_viewers",
+            JackalMsgKind::ChangeOwner => "change_owner",
+            JackalMsgKind::DeleteFile => "delete_file",
+            JackalMsgKind::ProvisionFileTree => "provision_file_tree",
+        }
+    }
+}
+
+impl fmt::Display for JackalMsgKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl JackalMsgResponse {
+    /// Decode the protobuf response `data` of a msg of `kind`
+    pub fn decode(kind: JackalMsgKind, data: &[u8]) -> Self {
+        match kind {
+            JackalMsgKind::PostFile => match MsgPostFileResponse::decode(data) {
+                Ok(response) => JackalMsgResponse::PostFile {
+                    path: response.path,
+                },
+                Err(_) => JackalMsgResponse::Raw {
+                    data: Binary::from(data),
+                },
+            },
+            _ if data.is_empty() => JackalMsgResponse::Empty {},
+            _ => JackalMsgResponse::Raw {
+                data: Binary::from(data),
+            },
+        }
+    }
+}
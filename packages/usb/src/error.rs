@@ -0,0 +1,64 @@
+use thiserror::Error;
+
+use crate::{versions::ProtocolVersion, JackalMsgKind};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum JackalError {
+    #[error("{msg} is not supported by canine-chain {version}")]
+    UnsupportedMsg {
+        msg: JackalMsgKind,
+        version: ProtocolVersion,
+    },
+
+    #[error("{query} queries are not supported by canine-chain {version}")]
+    UnsupportedQuery {
+        query: String,
+        version: ProtocolVersion,
+    },
+
+    #[error("{0}")]
+    FilePath(#[from] FilePathError),
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FilePathError {
+    #[error("empty path")]
+    Empty,
+
+    #[error("path {0} is not rooted at `s/`")]
+    NotRooted(String),
+
+    #[error("path {0} has an empty segment")]
+    EmptySegment(String),
+
+    #[error("invalid file name {0:?}")]
+    InvalidName(String),
+
+    #[error("the root folder has no parent")]
+    NoParent,
+}
+
+#[cfg(feature = "crypto")]
+#[derive(Error, Debug, PartialEq)]
+pub enum CryptoError {
+    #[error("expected {expected} bytes, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+
+    #[error("{0}")]
+    Hex(#[from] hex::FromHexError),
+
+    #[error("ecies: {0}")]
+    Ecies(String),
+
+    #[error("aes-gcm encryption failed")]
+    Encryption,
+
+    #[error("aes-gcm decryption failed, wrong key or corrupted data")]
+    Decryption,
+
+    #[error("malformed encrypted file: {0}")]
+    MalformedFile(String),
+
+    #[error("{0}")]
+    Std(#[from] cosmwasm_std::StdError),
+}
@@ -0,0 +1,84 @@
+//! # access_map
+//!
+//! Builder for the viewers and editors maps of filetree msgs, in the format the canine-chain keeper unmarshals:
+//! let c = concatenate( "v" | "e", trackingNumber, Bech32 address )
+//! map_key: hex[ hash(c) ]
+//! map_value: ECIES.encrypt( aesIV + aesKey )
+
+use std::collections::BTreeMap;
+
+use cosmwasm_std::to_json_string;
+
+use super::hash_and_hex;
+
+/// Kind of access granted by an [`AccessMap`]
+#[cosmwasm_schema::cw_serde]
+#[derive(Copy, Eq)]
+pub enum Access {
+    Viewer,
+    Editor,
+}
+
+impl Access {
+    /// prefix of the map keys, `v` for viewers and `e` for editors
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Access::Viewer => "v",
+            Access::Editor => "e",
+        }
+    }
+}
+
+/// Map of Bech32 addresses to their ECIES encrypted file key, hex encoded
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct AccessMap(pub BTreeMap<String, String>);
+
+impl AccessMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// grant `address` access with its encrypted file key
+    pub fn with(mut self, address: impl Into<String>, encrypted_key: impl Into<String>) -> Self {
+        self.insert(address, encrypted_key);
+        self
+    }
+
+    pub fn insert(&mut self, address: impl Into<String>, encrypted_key: impl Into<String>) {
+        self.0.insert(address.into(), encrypted_key.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// map keys of each address, in address order
+    pub fn ids(&self, access: Access, tracking_number: &str) -> Vec<String> {
+        self.0
+            .keys()
+            .map(|address| access_id(access, tracking_number, address))
+            .collect()
+    }
+
+    /// encrypted keys of each address, in address order
+    pub fn keys(&self) -> Vec<String> {
+        self.0.values().cloned().collect()
+    }
+
+    /// canonical json map posted on chain
+    pub fn to_json(&self, access: Access, tracking_number: &str) -> String {
+        let map: BTreeMap<String, &String> = self
+            .0
+            .iter()
+            .map(|(address, key)| (access_id(access, tracking_number, address), key))
+            .collect();
+        // a map of strings always serializes
+        to_json_string(&map).unwrap()
+    }
+}
+
+/// map key of `address`: hex[ hash( concatenate( prefix, trackingNumber, address ) ) ]
+pub fn access_id(access: Access, tracking_number: &str, address: &str) -> String {
+    hash_and_hex(&format!("{}{tracking_number}{address}", access.prefix()))
+}
@@ -0,0 +1,126 @@
+//! # file_path
+//!
+//! Validated path of a file or folder in a Jackal file tree, ex: `s/home/file.txt`.
+//! Every path is rooted at the `s` folder created by `MakeRoot`.
+
+use std::{fmt, str::FromStr};
+
+use cosmwasm_schema::{
+    schemars::JsonSchema,
+    serde::{Deserialize, Serialize},
+};
+
+use super::{hash_and_hex, merkle_path};
+use crate::error::FilePathError;
+
+/// name of the root folder of every file tree
+pub const ROOT: &str = "s";
+
+/// Normalized path, rooted at `s/` and without empty segments or trailing slash
+#[derive(
+    Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(crate = "cosmwasm_schema::serde", try_from = "String", into = "String")]
+#[schemars(crate = "cosmwasm_schema::schemars")]
+pub struct FilePath(#[schemars(with = "String")] String);
+
+impl FilePath {
+    /// validate and normalize `path`, trailing slashes are dropped
+    pub fn new(path: impl Into<String>) -> Result<Self, FilePathError> {
+        let path: String = path.into();
+        let trimmed = path.trim_end_matches('/');
+        if trimmed.is_empty() {
+            return Err(FilePathError::Empty);
+        }
+        let mut segments = trimmed.split('/');
+        if segments.next() != Some(ROOT) {
+            return Err(FilePathError::NotRooted(path));
+        }
+        if segments.any(str::is_empty) {
+            return Err(FilePathError::EmptySegment(path));
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    /// the `s` root folder
+    pub fn root() -> Self {
+        Self(ROOT.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0 == ROOT
+    }
+
+    /// folder containing this path, `None` for the root
+    pub fn parent(&self) -> Option<FilePath> {
+        self.0
+            .rsplit_once('/')
+            .map(|(parent, _)| Self(parent.to_string()))
+    }
+
+    /// last segment of the path, ex: `file.txt` for `s/home/file.txt`
+    pub fn child_name(&self) -> &str {
+        self.0.rsplit('/').next().unwrap_or(&self.0)
+    }
+
+    /// path of `name` inside this folder
+    pub fn join(&self, name: &str) -> Result<FilePath, FilePathError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(FilePathError::InvalidName(name.to_string()));
+        }
+        Ok(Self(format!("{}/{name}", self.0)))
+    }
+
+    /// whether this path is `ancestor` or one of its descendants
+    pub fn starts_with(&self, ancestor: &FilePath) -> bool {
+        self.0 == ancestor.0
+            || self
+                .0
+                .strip_prefix(&ancestor.0)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// full merkle path, ex: MerklePath("s/home/file.txt")
+    pub fn merkle_path(&self) -> String {
+        merkle_path(&self.0)
+    }
+
+    /// MerklePath of the parent and Hex[ hash( child name ) ], as posted in `MsgPostFile`.
+    /// Errors for the root, which has no parent.
+    pub fn hash_parent_child(&self) -> Result<(String, String), FilePathError> {
+        let parent = self.parent().ok_or(FilePathError::NoParent)?;
+        Ok((parent.merkle_path(), hash_and_hex(self.child_name())))
+    }
+}
+
+impl fmt::Display for FilePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for FilePath {
+    type Err = FilePathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::new(path)
+    }
+}
+
+impl TryFrom<String> for FilePath {
+    type Error = FilePathError;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        Self::new(path)
+    }
+}
+
+impl From<FilePath> for String {
+    fn from(path: FilePath) -> Self {
+        path.0
+    }
+}
@@ -35,6 +35,12 @@ pub fn merkle_path(path: &str) -> String {
     total
 }
 
+/// owner address of a file, as the file is stored by x/filetree:
+/// Hex[ hash( "o" + MerklePath + Hex[ hash( owner's Bech32 address ) ] ) ]
+pub fn owner_address(merkle_path: &str, owner: &str) -> String {
+    hash_and_hex(&format!("o{}{}", merkle_path, hash_and_hex(owner)))
+}
+
 /// return the merkle path of the parent and the hash_and_hex of the child
 pub fn merkle_helper(arg_hashpath: &str) -> (String, String) {
     let trim_path = arg_hashpath.trim_end_matches('/');
@@ -1,2 +1,6 @@
+pub mod access_map;
+pub mod file_path;
 pub mod filetree_helpers;
+pub use self::access_map::*;
+pub use self::file_path::*;
 pub use self::filetree_helpers::*;
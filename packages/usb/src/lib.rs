@@ -1,62 +1,229 @@
+#[cfg(feature = "crypto")]
+pub mod crypto;
+mod encoding;
+pub mod error;
 pub mod helpers;
+mod queries;
 pub mod types;
+pub mod versions;
+
+use helpers::{AccessMap, FilePath};
 
 #[cosmwasm_schema::cw_serde]
 pub enum JackalMsg {
     /// Add viewers to file
     AddViewers {
-        viewer_ids: String,
-        viewer_keys: String,
+        /// viewers to add, with the file key encrypted to each of them
+        viewers: AccessMap,
+        /// tracking number of the file
+        tracking_number: String,
         address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
         owner: String,
     },
     /// buy storage
     BuyStorage {
         for_address: String,
-        duration_days: u64,
-        bytes: u64,
+        duration_days: i64,
+        bytes: i64,
         payment_denom: String,
     },
-    ///
+    /// upgrade an existing storage plan
     UpgradeStorage {
         for_address: String,
-        duration_days: u64,
-        bytes: u64,
+        duration_days: i64,
+        bytes: i64,
         payment_denom: String,
     },
     /// cancel an active contract via cid
-    CancelContract {
-        cid: String,
+    CancelContract { cid: String },
+    /// delete a file or folder from the accounts storage on jackal
+    Delete {
+        /// full path of the file, ex: `s/home/file.txt`. hashed via merkle_path
+        path: FilePath,
+        /// Hex[ hash( owner's Bech32 address )]
+        account: String,
     },
-    Delete {},
     /// create absolute root folder for your accounts storage on jackal
     MakeRoot {
-        /// ?
-        editors: String,
-        /// ?
-        viewers: String,
-        /// unique id used in editors map??
+        editors: AccessMap,
+        viewers: AccessMap,
+        /// UUID used to derive the keys of the editors and viewers maps
         tracking_number: String,
     },
     /// create and save new file or folder.
     PostFile {
-        hash_parent: String,
-        hash_child: String,
+        /// full path of the file, ex: `s/home/file.txt`
+        path: FilePath,
+        /// FID of the file
         contents: String,
-        viewers: String,
-        editors: String,
+        viewers: AccessMap,
+        editors: AccessMap,
         tracking_number: String,
     },
     /// Post a ecies.PublicKey
-    PostKey {
-        key: String,
-    },
+    PostKey { key: String },
+    /// Remove viewers from file
     DeleteViewers {
-        viewer_ids: String,
+        /// Bech32 addresses of the viewers to remove
+        viewers: Vec<String>,
+        /// tracking number of the file
+        tracking_number: String,
         address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
         owner: String,
     },
+    /// sign a storage deal offered by a provider
     SignContract {
         cid: String,
+        /// pay for the file once instead of counting it against the storage plan
+        #[serde(default)]
+        pay_once: bool,
+    },
+    /// Add editors to file
+    AddEditors {
+        /// editors to add, with the file key encrypted to each of them
+        editors: AccessMap,
+        /// tracking number of the file
+        tracking_number: String,
+        address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
+        owner: String,
+    },
+    /// Remove editors from file
+    RemoveEditors {
+        /// Bech32 addresses of the editors to remove
+        editors: Vec<String>,
+        /// tracking number of the file
+        tracking_number: String,
+        address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
+        owner: String,
+    },
+    /// Remove every editor from file except the owner
+    ResetEditors {
+        address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
+        owner: String,
+    },
+    /// Remove every viewer from file except the owner
+    ResetViewers {
+        address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
+        owner: String,
+    },
+    /// Transfer ownership of a file
+    ChangeOwner {
+        address: String,
+        /// owner address the file is stored under, see [`helpers::owner_address`]
+        owner: String,
+        new_owner: String,
+    },
+    /// Delete a file via its merkle path
+    DeleteFile { hash_path: String, account: String },
+    /// Provision the file tree of an account
+    ProvisionFileTree {
+        editors: AccessMap,
+        viewers: AccessMap,
+        tracking_number: String,
+    },
+}
+
+/// Kind of a [`JackalMsg`], without its fields
+#[cosmwasm_schema::cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord, Hash)]
+pub enum JackalMsgKind {
+    AddViewers,
+    BuyStorage,
+    UpgradeStorage,
+    CancelContract,
+    Delete,
+    MakeRoot,
+    PostFile,
+    PostKey,
+    DeleteViewers,
+    SignContract,
+    AddEditors,
+    RemoveEditors,
+    ResetEditors,
+    ResetViewers,
+    ChangeOwner,
+    DeleteFile,
+    ProvisionFileTree,
+}
+
+/// Decoded response of a [`JackalMsg`] executed on jackal
+#[cosmwasm_schema::cw_serde]
+pub enum JackalMsgResponse {
+    /// `MsgPostFileResponse`
+    PostFile {
+        /// full merkle path of the posted file
+        path: String,
+    },
+    /// the msg has an empty response
+    Empty {},
+    /// response that doesn't decode as the expected msg response
+    Raw { data: cosmwasm_std::Binary },
+}
+
+/// Query of the state of jackal, sent to canine-chain as a stargate query
+#[cosmwasm_schema::cw_serde]
+pub enum JackalQuery {
+    /// file or folder at `path` in the file tree of `owner`
+    File {
+        path: FilePath,
+        /// Bech32 address of the owner of the file tree
+        owner: String,
+    },
+    /// public key posted by `address` with `PostKey`
+    PubKey { address: String },
+    /// storage plan bought for `address`
+    StoragePaymentInfo { address: String },
+    /// active storage deal of a file
+    ActiveDeals { cid: String },
+}
+
+/// Decoded response of a [`JackalQuery`] answered by jackal
+#[cosmwasm_schema::cw_serde]
+pub enum JackalQueryResponse {
+    /// `QueryFileResponse`
+    File {
+        /// merkle path of the file
+        address: String,
+        /// FID of the file, or folder contents
+        contents: String,
+        owner: String,
+        /// json encoded map of the viewers
+        viewing_access: String,
+        /// json encoded map of the editors
+        edit_access: String,
+        tracking_number: String,
+    },
+    /// `QueryPubkeyResponse`
+    PubKey { address: String, key: String },
+    /// `QueryStoragePaymentInfoResponse`
+    StoragePaymentInfo {
+        start: cosmwasm_std::Timestamp,
+        end: cosmwasm_std::Timestamp,
+        space_available: i64,
+        space_used: i64,
+        address: String,
+    },
+    /// `QueryActiveDealResponse`
+    ActiveDeals {
+        cid: String,
+        signee: String,
+        provider: String,
+        start_block: String,
+        end_block: String,
+        file_size: String,
+        proof_verified: String,
+        proofs_missed: String,
+        block_to_prove: String,
+        creator: String,
+        merkle: String,
+        fid: String,
     },
+    /// response that doesn't decode as the expected query response
+    Raw { data: cosmwasm_std::Binary },
 }
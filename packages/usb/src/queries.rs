@@ -0,0 +1,138 @@
+//! # queries
+//!
+//! Turns a [`JackalQuery`] into the canine-chain stargate query it represents on a given protocol version,
+//! and decodes the protobuf response jackal answers it with.
+
+use cosmwasm_std::{Binary, Empty, QueryRequest, Timestamp};
+use prost::Message;
+
+use crate::{
+    error::JackalError,
+    helpers::owner_address,
+    types::{
+        filetree::{QueryFileRequest, QueryPubkeyRequest},
+        storage::{QueryActiveDealRequest, QueryStoragePaymentInfoRequest},
+        JackalQueryType,
+    },
+    versions::ProtocolVersion,
+    JackalQuery, JackalQueryResponse,
+};
+
+impl JackalQuery {
+    /// snake_case name of the query, ex: `pub_key`
+    pub fn name(&self) -> &'static str {
+        match self {
+            JackalQuery::File { .. } => "file",
+            JackalQuery::PubKey { .. } => "pub_key",
+            JackalQuery::StoragePaymentInfo { .. } => "storage_payment_info",
+            JackalQuery::ActiveDeals { .. } => "active_deals",
+        }
+    }
+
+    /// Encode into a stargate query of `version`.
+    /// Errors for storage deals, which don't exist on v4.
+    pub fn to_query_request(
+        &self,
+        version: ProtocolVersion,
+    ) -> Result<QueryRequest<Empty>, JackalError> {
+        match self {
+            JackalQuery::File { path, owner } => {
+                let address = path.merkle_path();
+                Ok(QueryFileRequest {
+                    owner_address: owner_address(&address, owner),
+                    address,
+                }
+                .to_stargate_query())
+            }
+            JackalQuery::PubKey { address } => Ok(QueryPubkeyRequest {
+                address: address.clone(),
+            }
+            .to_stargate_query()),
+            JackalQuery::StoragePaymentInfo { address } => Ok(QueryStoragePaymentInfoRequest {
+                address: address.clone(),
+            }
+            .to_stargate_query()),
+            JackalQuery::ActiveDeals { .. } if version == ProtocolVersion::V4 => {
+                Err(JackalError::UnsupportedQuery {
+                    query: self.name().to_string(),
+                    version,
+                })
+            }
+            JackalQuery::ActiveDeals { cid } => {
+                Ok(QueryActiveDealRequest { cid: cid.clone() }.to_stargate_query())
+            }
+        }
+    }
+}
+
+impl JackalQueryResponse {
+    /// Decode the protobuf response `data` of `query`
+    pub fn decode(query: &JackalQuery, data: &[u8]) -> Self {
+        let decoded = match query {
+            JackalQuery::File { .. } => decode::<QueryFileRequest>(data).map(|response| {
+                let file = response.files.unwrap_or_default();
+                JackalQueryResponse::File {
+                    address: file.address,
+                    contents: file.contents,
+                    owner: file.owner,
+                    viewing_access: file.viewing_access,
+                    edit_access: file.edit_access,
+                    tracking_number: file.tracking_number,
+                }
+            }),
+            JackalQuery::PubKey { .. } => decode::<QueryPubkeyRequest>(data).map(|response| {
+                let pubkey = response.pubkey.unwrap_or_default();
+                JackalQueryResponse::PubKey {
+                    address: pubkey.address,
+                    key: pubkey.key,
+                }
+            }),
+            JackalQuery::StoragePaymentInfo { .. } => {
+                decode::<QueryStoragePaymentInfoRequest>(data).map(|response| {
+                    let info = response.storage_payment_info.unwrap_or_default();
+                    JackalQueryResponse::StoragePaymentInfo {
+                        start: timestamp(info.start),
+                        end: timestamp(info.end),
+                        space_available: info.space_available,
+                        space_used: info.space_used,
+                        address: info.address,
+                    }
+                })
+            }
+            JackalQuery::ActiveDeals { .. } => {
+                decode::<QueryActiveDealRequest>(data).map(|response| {
+                    let deal = response.active_deals.unwrap_or_default();
+                    JackalQueryResponse::ActiveDeals {
+                        cid: deal.cid,
+                        signee: deal.signee,
+                        provider: deal.provider,
+                        start_block: deal.startblock,
+                        end_block: deal.endblock,
+                        file_size: deal.filesize,
+                        proof_verified: deal.proofverified,
+                        proofs_missed: deal.proofsmissed,
+                        block_to_prove: deal.blocktoprove,
+                        creator: deal.creator,
+                        merkle: deal.merkle,
+                        fid: deal.fid,
+                    }
+                })
+            }
+        };
+        decoded.unwrap_or_else(|_| JackalQueryResponse::Raw {
+            data: Binary::from(data),
+        })
+    }
+}
+
+/// decode the response of the query `Q`
+fn decode<Q: JackalQueryType>(data: &[u8]) -> Result<Q::Response, prost::DecodeError> {
+    Q::Response::decode(data)
+}
+
+/// protobuf timestamp as a cosmwasm timestamp, the unix epoch when unset or negative
+fn timestamp(timestamp: Option<prost_types::Timestamp>) -> Timestamp {
+    let timestamp = timestamp.unwrap_or_default();
+    Timestamp::from_seconds(u64::try_from(timestamp.seconds).unwrap_or_default())
+        .plus_nanos(u64::try_from(timestamp.nanos).unwrap_or_default())
+}
@@ -1,2 +1,67 @@
+//! # types
+//!
+//! prost types of the canine-chain msgs and queries, generated from the protos vendored in `packages/usb/proto`.
+//! The generated code lives in `types/proto` and is regenerated with `just proto-gen`.
+
 pub mod filetree;
-pub mod storage;
\ No newline at end of file
+pub mod notifications;
+pub mod rns;
+pub mod storage;
+
+use cosmwasm_std::{Binary, CosmosMsg, Empty, QueryRequest};
+
+/// A canine-chain transaction msg, tied to its canonical protobuf type url
+pub trait JackalType: prost::Message + Sized {
+    /// type url of the msg on canine-chain, ex: `/canine_chain.filetree.MsgPostFile`
+    const TYPE_URL: &'static str;
+
+    /// encode the msg as a protobuf `Any` wrapped in a stargate msg
+    fn to_stargate_msg(&self) -> CosmosMsg {
+        CosmosMsg::Stargate {
+            type_url: Self::TYPE_URL.to_string(),
+            value: Binary(self.encode_to_vec()),
+        }
+    }
+}
+
+/// implement [`JackalType`] for each prost msg with its type url
+macro_rules! jackal_type_urls {
+    ($($msg:ty => $type_url:literal),* $(,)?) => {
+        $(
+            impl $crate::types::JackalType for $msg {
+                const TYPE_URL: &'static str = $type_url;
+            }
+        )*
+    };
+}
+pub(crate) use jackal_type_urls;
+
+/// A canine-chain query request, tied to the grpc path it is served at and the response it returns
+pub trait JackalQueryType: prost::Message + Sized {
+    /// grpc path of the query on canine-chain, ex: `/canine_chain.filetree.Query/Pubkey`
+    const PATH: &'static str;
+
+    /// prost type of the response
+    type Response: prost::Message + Default;
+
+    /// encode the request as a stargate query
+    fn to_stargate_query(&self) -> QueryRequest<Empty> {
+        QueryRequest::Stargate {
+            path: Self::PATH.to_string(),
+            data: Binary(self.encode_to_vec()),
+        }
+    }
+}
+
+/// implement [`JackalQueryType`] for each prost query request with its path and response
+macro_rules! jackal_query_paths {
+    ($($request:ty => $path:literal, $response:ty),* $(,)?) => {
+        $(
+            impl $crate::types::JackalQueryType for $request {
+                const PATH: &'static str = $path;
+                type Response = $response;
+            }
+        )*
+    };
+}
+pub(crate) use jackal_query_paths;
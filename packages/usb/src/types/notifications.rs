@@ -0,0 +1,15 @@
+//! # notifications
+//!
+//! Transaction msgs of canine-chain's notifications module, generated from `proto/canine_chain/notifications/tx.proto`.
+
+use super::jackal_type_urls;
+
+include!("proto/canine_chain.notifications.rs");
+
+jackal_type_urls! {
+    MsgCreateNotifications => "/canine_chain.notifications.MsgCreateNotifications",
+    MsgUpdateNotifications => "/canine_chain.notifications.MsgUpdateNotifications",
+    MsgDeleteNotifications => "/canine_chain.notifications.MsgDeleteNotifications",
+    MsgSetCounter => "/canine_chain.notifications.MsgSetCounter",
+    MsgBlockSenders => "/canine_chain.notifications.MsgBlockSenders",
+}
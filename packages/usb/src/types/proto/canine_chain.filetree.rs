@@ -0,0 +1,333 @@
+// This file is @generated by prost-build.
+/// Post a Files struct to chain.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostFile {
+    /// bech32 address of the creator and broadcaster of this message
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// Hex\[ hash( creator's bech32 address ) \]
+    #[prost(string, tag = "2")]
+    pub account: ::prost::alloc::string::String,
+    /// MerklePath of the parent folder, ex: MerklePath("s/home")
+    #[prost(string, tag = "3")]
+    pub hash_parent: ::prost::alloc::string::String,
+    /// Hex\[ hash( child name ) \], ex: Hex\[ hash("file.txt") \]
+    #[prost(string, tag = "4")]
+    pub hash_child: ::prost::alloc::string::String,
+    /// FID of the file, or folder contents
+    #[prost(string, tag = "5")]
+    pub contents: ::prost::alloc::string::String,
+    /// json encoded map of the viewers, see MsgMakeRootV2
+    #[prost(string, tag = "6")]
+    pub viewers: ::prost::alloc::string::String,
+    /// json encoded map of the editors, see MsgMakeRootV2
+    #[prost(string, tag = "7")]
+    pub editors: ::prost::alloc::string::String,
+    /// UUID used to derive the keys of the editors and viewers maps
+    #[prost(string, tag = "8")]
+    pub tracking_number: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostFileResponse {
+    /// full MerklePath of the posted file
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+}
+/// Grant read access to a file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddViewers {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// comma separated viewer ids, Hex\[ hash( "v" + trackingNumber + bech32 address ) \]
+    #[prost(string, tag = "2")]
+    pub viewer_ids: ::prost::alloc::string::String,
+    /// comma separated ECIES encrypted file keys, in the same order as viewer_ids
+    #[prost(string, tag = "3")]
+    pub viewer_keys: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "4")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "5")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddViewersResponse {}
+/// Post your public key to canine-chain filetree.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostKey {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// hex.encode( ecies.PublicKey )
+    #[prost(string, tag = "2")]
+    pub key: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostKeyResponse {}
+/// Delete a file owned by the creator.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeleteFile {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "2")]
+    pub hash_path: ::prost::alloc::string::String,
+    /// Hex\[ hash( owner's bech32 address ) \]
+    #[prost(string, tag = "3")]
+    pub account: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeleteFileResponse {}
+/// Delete a file or folder, regardless of its children.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDangerDelete {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// MerklePath of the file or folder
+    #[prost(string, tag = "2")]
+    pub hash_path: ::prost::alloc::string::String,
+    /// Hex\[ hash( owner's bech32 address ) \]
+    #[prost(string, tag = "3")]
+    pub account: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDangerDeleteResponse {}
+/// Revoke read access to a file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveViewers {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// comma separated viewer ids
+    #[prost(string, tag = "2")]
+    pub viewer_ids: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "3")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "4")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveViewersResponse {}
+/// Deprecated in favour of MsgMakeRootV2.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMakeRoot {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub root_hash_path: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub contents: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub editors: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub viewers: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub tracking_number: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMakeRootResponse {}
+/// Create the absolute root folder of a storage account.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMakeRootV2 {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// json encoded map with:
+    ///    let c = concatenate( "e", trackingNumber, bech32 address )
+    ///    map_key: Hex\[ hash(c) \]
+    ///    map_value: ECIES.encrypt( aesIV + aesKey )
+    /// map keys and values must be strings or unmarshalling in the keeper fails
+    #[prost(string, tag = "2")]
+    pub editors: ::prost::alloc::string::String,
+    /// same as editors, with c = concatenate( "v", trackingNumber, bech32 address )
+    #[prost(string, tag = "3")]
+    pub viewers: ::prost::alloc::string::String,
+    /// UUID used to derive the keys of the editors and viewers maps
+    #[prost(string, tag = "4")]
+    pub tracking_number: ::prost::alloc::string::String,
+}
+/// Grant write access to a file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddEditors {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// comma separated editor ids, Hex\[ hash( "e" + trackingNumber + bech32 address ) \]
+    #[prost(string, tag = "2")]
+    pub editor_ids: ::prost::alloc::string::String,
+    /// comma separated ECIES encrypted file keys, in the same order as editor_ids
+    #[prost(string, tag = "3")]
+    pub editor_keys: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "4")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "5")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddEditorsResponse {}
+/// Revoke write access to a file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveEditors {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// comma separated editor ids
+    #[prost(string, tag = "2")]
+    pub editor_ids: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "3")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "4")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveEditorsResponse {}
+/// Remove every editor from a file except the owner.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgResetEditors {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "3")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgResetEditorsResponse {}
+/// Remove every viewer from a file except the owner.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgResetViewers {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "3")]
+    pub file_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgResetViewersResponse {}
+/// Transfer ownership of a file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChangeOwner {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// MerklePath of the file
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    /// owner address the file is stored under, Hex\[ hash( "o" + MerklePath + account ) \] with the account of the owner
+    #[prost(string, tag = "3")]
+    pub file_owner: ::prost::alloc::string::String,
+    /// Hex\[ hash( new owner's bech32 address ) \]
+    #[prost(string, tag = "4")]
+    pub new_owner: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChangeOwnerResponse {}
+/// Provision the file tree of the creator, with the same arguments as MsgMakeRootV2.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgProvisionFileTree {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub editors: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub viewers: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub tracking_number: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgProvisionFileTreeResponse {}
+/// A file or folder of a file tree.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Files {
+    /// MerklePath of the file
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    /// FID of the file, or folder contents
+    #[prost(string, tag = "2")]
+    pub contents: ::prost::alloc::string::String,
+    /// owner address, see QueryFileRequest
+    #[prost(string, tag = "3")]
+    pub owner: ::prost::alloc::string::String,
+    /// json encoded map of the viewers
+    #[prost(string, tag = "4")]
+    pub viewing_access: ::prost::alloc::string::String,
+    /// json encoded map of the editors
+    #[prost(string, tag = "5")]
+    pub edit_access: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub tracking_number: ::prost::alloc::string::String,
+}
+/// A public key posted with MsgPostKey.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Pubkey {
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub key: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFileRequest {
+    /// MerklePath of the file
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    /// Hex\[ hash( "o" + MerklePath + Hex[ hash( owner's bech32 address ) \] ) ]
+    #[prost(string, tag = "2")]
+    pub owner_address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFileResponse {
+    #[prost(message, optional, tag = "1")]
+    pub files: ::core::option::Option<Files>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryPubkeyRequest {
+    /// bech32 address that posted the key
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryPubkeyResponse {
+    #[prost(message, optional, tag = "1")]
+    pub pubkey: ::core::option::Option<Pubkey>,
+}
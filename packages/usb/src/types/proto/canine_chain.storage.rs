@@ -0,0 +1,271 @@
+// This file is @generated by prost-build.
+/// Offer a storage deal to a user, posted by a provider.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostContract {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub merkle: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub signee: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub filesize: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub fid: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostContractResponse {}
+/// Prove a provider still stores the chunk of a deal.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostproof {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub item: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub hashlist: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub cid: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPostproofResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub error_message: ::prost::alloc::string::String,
+}
+/// Accept a storage deal posted by a provider.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSignContract {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// id of the contract to sign
+    #[prost(string, tag = "2")]
+    pub cid: ::prost::alloc::string::String,
+    /// pay for the file once, instead of counting it against the storage plan
+    #[prost(bool, tag = "3")]
+    pub pay_once: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSignContractResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderIp {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub ip: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderIpResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderKeybase {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub keybase: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderKeybaseResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderTotalspace {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub space: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSetProviderTotalspaceResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgInitProvider {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub ip: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub keybase: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub totalspace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgInitProviderResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgShutdownProvider {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgShutdownProviderResponse {}
+/// Cancel an active storage deal.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCancelContract {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// id of the contract to cancel
+    #[prost(string, tag = "2")]
+    pub cid: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCancelContractResponse {}
+/// Buy a storage plan.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgBuyStorage {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    /// bech32 address the storage plan is bought for
+    #[prost(string, tag = "2")]
+    pub for_address: ::prost::alloc::string::String,
+    /// duration of the plan in days
+    #[prost(int64, tag = "3")]
+    pub duration_days: i64,
+    /// storage space of the plan in bytes
+    #[prost(int64, tag = "4")]
+    pub bytes: i64,
+    /// denom the plan is paid in
+    #[prost(string, tag = "5")]
+    pub payment_denom: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgBuyStorageResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgClaimStray {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub cid: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub for_address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgClaimStrayResponse {}
+/// Upgrade an existing storage plan, with the same arguments as MsgBuyStorage.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpgradeStorage {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub for_address: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub duration_days: i64,
+    #[prost(int64, tag = "4")]
+    pub bytes: i64,
+    #[prost(string, tag = "5")]
+    pub payment_denom: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpgradeStorageResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddClaimer {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub claim_address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgAddClaimerResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveClaimer {
+    #[prost(string, tag = "1")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub claim_address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRemoveClaimerResponse {}
+/// A storage deal signed by a provider, proven every `blocktoprove` blocks.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActiveDeals {
+    #[prost(string, tag = "1")]
+    pub cid: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub signee: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub provider: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub startblock: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub endblock: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub filesize: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub proofverified: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub proofsmissed: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub blocktoprove: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "11")]
+    pub merkle: ::prost::alloc::string::String,
+    #[prost(string, tag = "12")]
+    pub fid: ::prost::alloc::string::String,
+}
+/// The storage plan of an account.
+/// Field 6, the coins paid for the plan, is left out.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StoragePaymentInfo {
+    #[prost(message, optional, tag = "1")]
+    pub start: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "2")]
+    pub end: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(int64, tag = "3")]
+    pub space_available: i64,
+    #[prost(int64, tag = "4")]
+    pub space_used: i64,
+    #[prost(string, tag = "5")]
+    pub address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryActiveDealRequest {
+    #[prost(string, tag = "1")]
+    pub cid: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryActiveDealResponse {
+    #[prost(message, optional, tag = "1")]
+    pub active_deals: ::core::option::Option<ActiveDeals>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryStoragePaymentInfoRequest {
+    /// bech32 address the storage was bought for
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryStoragePaymentInfoResponse {
+    #[prost(message, optional, tag = "1")]
+    pub storage_payment_info: ::core::option::Option<StoragePaymentInfo>,
+}
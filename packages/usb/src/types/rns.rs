@@ -0,0 +1,22 @@
+//! # rns
+//!
+//! Transaction msgs of canine-chain's name service, generated from `proto/canine_chain/rns/tx.proto`.
+
+use super::jackal_type_urls;
+
+include!("proto/canine_chain.rns.rs");
+
+jackal_type_urls! {
+    MsgRegister => "/canine_chain.rns.MsgRegister",
+    MsgBid => "/canine_chain.rns.MsgBid",
+    MsgAcceptBid => "/canine_chain.rns.MsgAcceptBid",
+    MsgCancelBid => "/canine_chain.rns.MsgCancelBid",
+    MsgList => "/canine_chain.rns.MsgList",
+    MsgBuy => "/canine_chain.rns.MsgBuy",
+    MsgDelist => "/canine_chain.rns.MsgDelist",
+    MsgTransfer => "/canine_chain.rns.MsgTransfer",
+    MsgAddRecord => "/canine_chain.rns.MsgAddRecord",
+    MsgDelRecord => "/canine_chain.rns.MsgDelRecord",
+    MsgInit => "/canine_chain.rns.MsgInit",
+    MsgUpdate => "/canine_chain.rns.MsgUpdate",
+}
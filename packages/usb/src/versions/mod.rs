@@ -0,0 +1,37 @@
+//! # versions
+//!
+//! canine-chain protocol versions and the msg set each of them supports.
+//! Each version module maps a [`JackalMsg`] onto the msg shape and type url of that version.
+
+pub mod v3;
+pub mod v4;
+
+use std::fmt;
+
+use crate::{error::JackalError, JackalMsg};
+
+/// Version of canine-chain running on the target jackal network
+#[cosmwasm_schema::cw_serde]
+#[derive(Copy, Default, Eq)]
+pub enum ProtocolVersion {
+    #[default]
+    V3,
+    V4,
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolVersion::V3 => write!(f, "v3"),
+            ProtocolVersion::V4 => write!(f, "v4"),
+        }
+    }
+}
+
+/// error for a msg that has no counterpart on `version`
+pub(crate) fn unsupported(msg: &JackalMsg, version: ProtocolVersion) -> JackalError {
+    JackalError::UnsupportedMsg {
+        msg: msg.kind(),
+        version,
+    }
+}
@@ -0,0 +1,222 @@
+//! # v3
+//!
+//! canine-chain v3 supports every [`JackalMsg`], using the msgs generated from the vendored protos.
+
+use cosmwasm_std::CosmosMsg;
+
+use crate::{
+    error::JackalError,
+    helpers::{access_id, hash_and_hex, Access},
+    types::{
+        filetree::{
+            MsgAddEditors, MsgAddViewers, MsgChangeOwner, MsgDangerDelete, MsgDeleteFile,
+            MsgMakeRootV2, MsgPostFile, MsgPostKey, MsgProvisionFileTree, MsgRemoveEditors,
+            MsgRemoveViewers, MsgResetEditors, MsgResetViewers,
+        },
+        storage::{MsgBuyStorage, MsgCancelContract, MsgSignContract, MsgUpgradeStorage},
+        JackalType,
+    },
+    JackalMsg,
+};
+
+/// Type url of the v3 msg `msg` is encoded to
+pub fn type_url(msg: &JackalMsg) -> &'static str {
+    match msg {
+        JackalMsg::AddViewers { .. } => MsgAddViewers::TYPE_URL,
+        JackalMsg::BuyStorage { .. } => MsgBuyStorage::TYPE_URL,
+        JackalMsg::UpgradeStorage { .. } => MsgUpgradeStorage::TYPE_URL,
+        JackalMsg::CancelContract { .. } => MsgCancelContract::TYPE_URL,
+        JackalMsg::Delete { .. } => MsgDangerDelete::TYPE_URL,
+        JackalMsg::MakeRoot { .. } => MsgMakeRootV2::TYPE_URL,
+        JackalMsg::PostFile { .. } => MsgPostFile::TYPE_URL,
+        JackalMsg::PostKey { .. } => MsgPostKey::TYPE_URL,
+        JackalMsg::DeleteViewers { .. } => MsgRemoveViewers::TYPE_URL,
+        JackalMsg::SignContract { .. } => MsgSignContract::TYPE_URL,
+        JackalMsg::AddEditors { .. } => MsgAddEditors::TYPE_URL,
+        JackalMsg::RemoveEditors { .. } => MsgRemoveEditors::TYPE_URL,
+        JackalMsg::ResetEditors { .. } => MsgResetEditors::TYPE_URL,
+        JackalMsg::ResetViewers { .. } => MsgResetViewers::TYPE_URL,
+        JackalMsg::ChangeOwner { .. } => MsgChangeOwner::TYPE_URL,
+        JackalMsg::DeleteFile { .. } => MsgDeleteFile::TYPE_URL,
+        JackalMsg::ProvisionFileTree { .. } => MsgProvisionFileTree::TYPE_URL,
+    }
+}
+
+/// Encode `msg` into a v3 stargate msg signed by `creator`.
+/// The account hash of `PostFile` is derived from `creator`.
+pub fn encode(msg: JackalMsg, creator: &str) -> Result<CosmosMsg, JackalError> {
+    let creator = creator.to_string();
+    let msg = match msg {
+        JackalMsg::AddViewers {
+            viewers,
+            tracking_number,
+            address,
+            owner,
+        } => MsgAddViewers {
+            creator,
+            viewer_ids: viewers.ids(Access::Viewer, &tracking_number).join(","),
+            viewer_keys: viewers.keys().join(","),
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::BuyStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } => MsgBuyStorage {
+            creator,
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        }
+        .to_stargate_msg(),
+        JackalMsg::UpgradeStorage {
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        } => MsgUpgradeStorage {
+            creator,
+            for_address,
+            duration_days,
+            bytes,
+            payment_denom,
+        }
+        .to_stargate_msg(),
+        JackalMsg::CancelContract { cid } => MsgCancelContract { creator, cid }.to_stargate_msg(),
+        JackalMsg::Delete { path, account } => MsgDangerDelete {
+            creator,
+            hash_path: path.merkle_path(),
+            account,
+        }
+        .to_stargate_msg(),
+        JackalMsg::MakeRoot {
+            editors,
+            viewers,
+            tracking_number,
+        } => MsgMakeRootV2 {
+            creator,
+            editors: editors.to_json(Access::Editor, &tracking_number),
+            viewers: viewers.to_json(Access::Viewer, &tracking_number),
+            tracking_number,
+        }
+        .to_stargate_msg(),
+        JackalMsg::PostFile {
+            path,
+            contents,
+            viewers,
+            editors,
+            tracking_number,
+        } => {
+            let (hash_parent, hash_child) = path.hash_parent_child()?;
+            MsgPostFile {
+                account: hash_and_hex(&creator),
+                creator,
+                hash_parent,
+                hash_child,
+                contents,
+                viewers: viewers.to_json(Access::Viewer, &tracking_number),
+                editors: editors.to_json(Access::Editor, &tracking_number),
+                tracking_number,
+            }
+            .to_stargate_msg()
+        }
+        JackalMsg::PostKey { key } => MsgPostKey { creator, key }.to_stargate_msg(),
+        JackalMsg::DeleteViewers {
+            viewers,
+            tracking_number,
+            address,
+            owner,
+        } => MsgRemoveViewers {
+            creator,
+            viewer_ids: removed_ids(Access::Viewer, &tracking_number, &viewers),
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::SignContract { cid, pay_once } => MsgSignContract {
+            creator,
+            cid,
+            pay_once,
+        }
+        .to_stargate_msg(),
+        JackalMsg::AddEditors {
+            editors,
+            tracking_number,
+            address,
+            owner,
+        } => MsgAddEditors {
+            creator,
+            editor_ids: editors.ids(Access::Editor, &tracking_number).join(","),
+            editor_keys: editors.keys().join(","),
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::RemoveEditors {
+            editors,
+            tracking_number,
+            address,
+            owner,
+        } => MsgRemoveEditors {
+            creator,
+            editor_ids: removed_ids(Access::Editor, &tracking_number, &editors),
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::ResetEditors { address, owner } => MsgResetEditors {
+            creator,
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::ResetViewers { address, owner } => MsgResetViewers {
+            creator,
+            address,
+            file_owner: owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::ChangeOwner {
+            address,
+            owner,
+            new_owner,
+        } => MsgChangeOwner {
+            creator,
+            address,
+            file_owner: owner,
+            new_owner,
+        }
+        .to_stargate_msg(),
+        JackalMsg::DeleteFile { hash_path, account } => MsgDeleteFile {
+            creator,
+            hash_path,
+            account,
+        }
+        .to_stargate_msg(),
+        JackalMsg::ProvisionFileTree {
+            editors,
+            viewers,
+            tracking_number,
+        } => MsgProvisionFileTree {
+            creator,
+            editors: editors.to_json(Access::Editor, &tracking_number),
+            viewers: viewers.to_json(Access::Viewer, &tracking_number),
+            tracking_number,
+        }
+        .to_stargate_msg(),
+    };
+    Ok(msg)
+}
+
+/// comma separated map keys of the `addresses` losing `access`
+fn removed_ids(access: Access, tracking_number: &str, addresses: &[String]) -> String {
+    addresses
+        .iter()
+        .map(|address| access_id(access, tracking_number, address))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,47 @@
+//! # v4
+//!
+//! canine-chain v4 replaced storage contracts with files and proofs, so deals can no longer be
+//! signed or cancelled, and root folders are provisioned via `MsgProvisionFileTree`.
+//! Every other [`JackalMsg`] keeps its v3 shape.
+
+use cosmwasm_std::CosmosMsg;
+
+use super::{unsupported, v3, ProtocolVersion};
+use crate::{
+    error::JackalError,
+    helpers::Access,
+    types::{filetree::MsgProvisionFileTree, JackalType},
+    JackalMsg,
+};
+
+/// Type url of the v4 msg `msg` is encoded to
+pub fn type_url(msg: &JackalMsg) -> Result<&'static str, JackalError> {
+    match msg {
+        JackalMsg::MakeRoot { .. } => Ok(MsgProvisionFileTree::TYPE_URL),
+        JackalMsg::Delete { .. }
+        | JackalMsg::SignContract { .. }
+        | JackalMsg::CancelContract { .. } => Err(unsupported(msg, ProtocolVersion::V4)),
+        msg => Ok(v3::type_url(msg)),
+    }
+}
+
+/// Encode `msg` into a v4 stargate msg signed by `creator`.
+pub fn encode(msg: JackalMsg, creator: &str) -> Result<CosmosMsg, JackalError> {
+    match msg {
+        JackalMsg::MakeRoot {
+            editors,
+            viewers,
+            tracking_number,
+        } => Ok(MsgProvisionFileTree {
+            creator: creator.to_string(),
+            editors: editors.to_json(Access::Editor, &tracking_number),
+            viewers: viewers.to_json(Access::Viewer, &tracking_number),
+            tracking_number,
+        }
+        .to_stargate_msg()),
+        JackalMsg::Delete { .. }
+        | JackalMsg::SignContract { .. }
+        | JackalMsg::CancelContract { .. } => Err(unsupported(&msg, ProtocolVersion::V4)),
+        msg => v3::encode(msg, creator),
+    }
+}
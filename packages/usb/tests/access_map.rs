@@ -0,0 +1,74 @@
+use cosmwasm_std::{from_json, to_json_string};
+use usb::helpers::{access_id, hash_and_hex, Access, AccessMap};
+
+const ALICE: &str = "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4";
+const BOB: &str = "jkl1h4a7pfuqmvqxqmyg8c2pxsctx4ngxqqpq9jrpl";
+
+#[test]
+fn access_id_matches_the_keeper_format() {
+    assert_eq!(
+        access_id(Access::Viewer, "tracking", ALICE),
+        hash_and_hex(&format!("vtracking{ALICE}"))
+    );
+    assert_eq!(
+        access_id(Access::Editor, "tracking", ALICE),
+        hash_and_hex(&format!("etracking{ALICE}"))
+    );
+}
+
+#[test]
+fn canonical_json() {
+    // insertion order doesn't matter, the json is always sorted by map key
+    let map = AccessMap::new()
+        .with(BOB, "bob_key")
+        .with(ALICE, "alice_key");
+    let reversed = AccessMap::new()
+        .with(ALICE, "alice_key")
+        .with(BOB, "bob_key");
+    assert_eq!(
+        map.to_json(Access::Viewer, "tracking"),
+        reversed.to_json(Access::Viewer, "tracking")
+    );
+
+    let alice_id = access_id(Access::Viewer, "tracking", ALICE);
+    let bob_id = access_id(Access::Viewer, "tracking", BOB);
+    let (first, second) = if alice_id < bob_id {
+        ((&alice_id, "alice_key"), (&bob_id, "bob_key"))
+    } else {
+        ((&bob_id, "bob_key"), (&alice_id, "alice_key"))
+    };
+    assert_eq!(
+        map.to_json(Access::Viewer, "tracking"),
+        format!(
+            r#"{{"{}":"{}","{}":"{}"}}"#,
+            first.0, first.1, second.0, second.1
+        )
+    );
+}
+
+#[test]
+fn empty_map() {
+    assert_eq!(AccessMap::new().to_json(Access::Editor, "tracking"), "{}");
+}
+
+#[test]
+fn ids_and_keys_are_aligned() {
+    let map = AccessMap::new()
+        .with(BOB, "bob_key")
+        .with(ALICE, "alice_key");
+    let ids = map.ids(Access::Editor, "tracking");
+    let keys = map.keys();
+    assert_eq!(ids.len(), 2);
+    for (id, key) in ids.iter().zip(keys) {
+        let address = if key == "alice_key" { ALICE } else { BOB };
+        assert_eq!(id, &access_id(Access::Editor, "tracking", address));
+    }
+}
+
+#[test]
+fn serializes_as_address_map() {
+    let map = AccessMap::new().with(ALICE, "alice_key");
+    let json = to_json_string(&map).unwrap();
+    assert_eq!(json, format!(r#"{{"{ALICE}":"alice_key"}}"#));
+    assert_eq!(from_json::<AccessMap>(json).unwrap(), map);
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "crypto")]
+
+use usb::{
+    crypto::{
+        decrypt_file, ecies_decrypt, encrypt_file, public_key, FileDetails, FileKey, IV_LENGTH,
+    },
+    error::CryptoError,
+};
+
+// vectors shared by eciesjs, the ECIES implementation of the Jackal JS client
+const AES_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const AES_IV: &str = "f3e1ba810d2c8900b11312b7c725565f";
+const AES_TAG: &str = "ec3b71e17c11dbe31484da9450edcf6c";
+const AES_CIPHERTEXT: &str = "02d2ffed93b856f148b9";
+const ECIES_SECRET: &str = "e520872701d9ec44dbac2eab85512ad14ad0c42e01de56d7b528abd8524fcb47";
+const ECIES_CIPHERTEXT: &str = "047be1885aeb48d4d4db0c992996725d3264784fef88c5b60782f8d0f940c213227fc3f904f846d5ec3d0fba6653754501e8ebadc421aa3892a20fef33cff0206047058a4cfb4efbeae96b2d019b4ab2edce33328748a0d008a69c8f5816b72d45bd9b5a41bb6ea0127ab23057ec6fcd";
+
+// file encrypted with the file encryption of jackal.js (`convertToEncryptedFile`), see fixtures/jackal_js_file.mjs
+const JS_FILE: &str = include_str!("fixtures/jackal_js_file.hex");
+
+fn known_key() -> FileKey {
+    let mut bytes = hex::decode(AES_IV).unwrap();
+    bytes.extend(hex::decode(AES_KEY).unwrap());
+    FileKey::from_bytes(&bytes).unwrap()
+}
+
+fn details(size: u64) -> FileDetails {
+    FileDetails {
+        name: "file.txt".to_string(),
+        last_modified: 1_700_000_000_000,
+        file_type: "text/plain".to_string(),
+        size,
+    }
+}
+
+#[test]
+fn aes_known_vector() {
+    // webcrypto appends the tag to the ciphertext
+    let expected = hex::decode(format!("{AES_CIPHERTEXT}{AES_TAG}")).unwrap();
+    assert_eq!(known_key().encrypt(b"helloworld").unwrap(), expected);
+    assert_eq!(known_key().decrypt(&expected).unwrap(), b"helloworld");
+}
+
+#[test]
+fn ecies_known_vector() {
+    let decrypted = ecies_decrypt(
+        &hex::decode(ECIES_SECRET).unwrap(),
+        &hex::decode(ECIES_CIPHERTEXT).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(decrypted, "hello world🌍".as_bytes());
+}
+
+#[test]
+fn wrap_and_unwrap_file_key() {
+    let secret = hex::decode(ECIES_SECRET).unwrap();
+    let file_key = FileKey::generate();
+
+    let wrapped = file_key.wrap_for(&public_key(&secret).unwrap()).unwrap();
+    assert_eq!(FileKey::unwrap_with(&wrapped, &secret).unwrap(), file_key);
+
+    // the wrapped key is aesIV + aesKey
+    let unwrapped = ecies_decrypt(&secret, &hex::decode(&wrapped).unwrap()).unwrap();
+    assert_eq!(&unwrapped[..IV_LENGTH], &file_key.iv);
+    assert_eq!(&unwrapped[IV_LENGTH..], &file_key.key);
+}
+
+#[test]
+fn file_format() {
+    let key = known_key();
+    let contents = b"hello jackal";
+    let encrypted = encrypt_file(&key, &details(contents.len() as u64), contents).unwrap();
+
+    // details segment, followed by a single contents segment
+    let details_json =
+        r#"{"name":"file.txt","lastModified":1700000000000,"type":"text/plain","size":12}"#;
+    let details_len = details_json.len() + 16;
+    assert_eq!(&encrypted[..8], format!("{details_len:08}").as_bytes());
+    assert_eq!(
+        key.decrypt(&encrypted[8..8 + details_len]).unwrap(),
+        details_json.as_bytes()
+    );
+    let contents_segment = &encrypted[8 + details_len..];
+    assert_eq!(&contents_segment[..8], b"00000028");
+    assert_eq!(
+        key.decrypt(&contents_segment[8..]).unwrap(),
+        contents.to_vec()
+    );
+
+    assert_eq!(
+        decrypt_file(&key, &encrypted).unwrap(),
+        (details(contents.len() as u64), contents.to_vec())
+    );
+}
+
+#[test]
+fn decrypt_jackal_js_file() {
+    let key = FileKey {
+        key: std::array::from_fn(|i| i as u8),
+        iv: std::array::from_fn(|i| 0xf0 + i as u8),
+    };
+    let encrypted = hex::decode(JS_FILE.trim()).unwrap();
+    let contents = b"hello jackal, from the js client";
+    assert_eq!(
+        decrypt_file(&key, &encrypted).unwrap(),
+        (
+            FileDetails {
+                name: "hello.txt".to_string(),
+                last_modified: 1_700_000_000_000,
+                file_type: "text/plain".to_string(),
+                size: contents.len() as u64,
+            },
+            contents.to_vec()
+        )
+    );
+    // and the other way around
+    assert_eq!(
+        encrypt_file(&key, &decrypt_file(&key, &encrypted).unwrap().0, contents).unwrap(),
+        encrypted
+    );
+}
+
+#[test]
+fn oversized_details() {
+    // the size in the details isn't trusted
+    let encrypted = encrypt_file(&known_key(), &details(u64::MAX), b"abc").unwrap();
+    assert_eq!(
+        decrypt_file(&known_key(), &encrypted).unwrap(),
+        (details(u64::MAX), b"abc".to_vec())
+    );
+}
+
+#[test]
+fn decrypt_with_wrong_key() {
+    let encrypted = encrypt_file(&known_key(), &details(0), &[]).unwrap();
+    assert_eq!(
+        decrypt_file(&FileKey::generate(), &encrypted).unwrap_err(),
+        CryptoError::Decryption
+    );
+}
+
+#[test]
+fn malformed_file() {
+    let encrypted = encrypt_file(&known_key(), &details(3), b"abc").unwrap();
+    assert!(matches!(
+        decrypt_file(&known_key(), &encrypted[..encrypted.len() - 1]).unwrap_err(),
+        CryptoError::MalformedFile(_)
+    ));
+    assert!(matches!(
+        decrypt_file(&known_key(), b"not a jackal file").unwrap_err(),
+        CryptoError::MalformedFile(_)
+    ));
+}
@@ -0,0 +1,455 @@
+use cosmwasm_std::CosmosMsg;
+use prost::Message;
+use usb::{
+    error::{FilePathError, JackalError},
+    helpers::{access_id, hash_and_hex, merkle_path, Access, AccessMap, FilePath},
+    types::{
+        filetree::{
+            MsgAddEditors, MsgAddViewers, MsgChangeOwner, MsgDangerDelete, MsgDeleteFile,
+            MsgMakeRootV2, MsgPostFile, MsgPostFileResponse, MsgPostKey, MsgProvisionFileTree,
+            MsgRemoveEditors, MsgRemoveViewers, MsgResetEditors, MsgResetViewers,
+        },
+        storage::{MsgBuyStorage, MsgCancelContract, MsgSignContract, MsgUpgradeStorage},
+        JackalType,
+    },
+    versions::ProtocolVersion,
+    JackalMsg, JackalMsgKind, JackalMsgResponse,
+};
+
+const CREATOR: &str = "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4";
+const FRIEND: &str = "jkl1h4a7pfuqmvqxqmyg8c2pxsctx4ngxqqpq9jrpl";
+
+fn access() -> AccessMap {
+    AccessMap::new()
+        .with(CREATOR, "creator_key")
+        .with(FRIEND, "friend_key")
+}
+
+/// encode `msg` as a v3 msg and decode it back into the prost msg matching its type url
+fn round_trip<T: JackalType + Default>(msg: JackalMsg) -> T {
+    let type_url = msg.type_url(ProtocolVersion::V3).unwrap();
+    assert_eq!(type_url, T::TYPE_URL);
+
+    match msg.into_cosmos_msg(ProtocolVersion::V3, CREATOR).unwrap() {
+        CosmosMsg::Stargate {
+            type_url: encoded_type_url,
+            value,
+        } => {
+            assert_eq!(encoded_type_url, type_url);
+            T::decode(value.as_slice()).unwrap()
+        }
+        other => panic!("expected a stargate msg, got {other:?}"),
+    }
+}
+
+#[test]
+fn make_root() {
+    let decoded: MsgMakeRootV2 = round_trip(JackalMsg::MakeRoot {
+        editors: access(),
+        viewers: access(),
+        tracking_number: "tracking".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgMakeRootV2 {
+            creator: CREATOR.to_string(),
+            editors: access().to_json(Access::Editor, "tracking"),
+            viewers: access().to_json(Access::Viewer, "tracking"),
+            tracking_number: "tracking".to_string(),
+        }
+    );
+}
+
+#[test]
+fn post_file() {
+    let decoded: MsgPostFile = round_trip(JackalMsg::PostFile {
+        path: FilePath::new("s/home/file.txt").unwrap(),
+        contents: "fid".to_string(),
+        viewers: access(),
+        editors: access(),
+        tracking_number: "tracking".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgPostFile {
+            creator: CREATOR.to_string(),
+            account: hash_and_hex(CREATOR),
+            hash_parent: merkle_path("s/home"),
+            hash_child: hash_and_hex("file.txt"),
+            contents: "fid".to_string(),
+            viewers: access().to_json(Access::Viewer, "tracking"),
+            editors: access().to_json(Access::Editor, "tracking"),
+            tracking_number: "tracking".to_string(),
+        }
+    );
+}
+
+#[test]
+fn post_file_at_root() {
+    let msg = JackalMsg::PostFile {
+        path: FilePath::root(),
+        contents: "fid".to_string(),
+        viewers: access(),
+        editors: access(),
+        tracking_number: "tracking".to_string(),
+    };
+    assert_eq!(
+        msg.into_cosmos_msg(ProtocolVersion::V3, CREATOR),
+        Err(JackalError::FilePath(FilePathError::NoParent))
+    );
+}
+
+#[test]
+fn post_key() {
+    let decoded: MsgPostKey = round_trip(JackalMsg::PostKey {
+        key: "key".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgPostKey {
+            creator: CREATOR.to_string(),
+            key: "key".to_string(),
+        }
+    );
+}
+
+#[test]
+fn add_viewers() {
+    let decoded: MsgAddViewers = round_trip(JackalMsg::AddViewers {
+        viewers: AccessMap::new().with(FRIEND, "friend_key"),
+        tracking_number: "tracking".to_string(),
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgAddViewers {
+            creator: CREATOR.to_string(),
+            viewer_ids: access_id(Access::Viewer, "tracking", FRIEND),
+            viewer_keys: "friend_key".to_string(),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn delete_viewers() {
+    let decoded: MsgRemoveViewers = round_trip(JackalMsg::DeleteViewers {
+        viewers: vec![CREATOR.to_string(), FRIEND.to_string()],
+        tracking_number: "tracking".to_string(),
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgRemoveViewers {
+            creator: CREATOR.to_string(),
+            viewer_ids: format!(
+                "{},{}",
+                access_id(Access::Viewer, "tracking", CREATOR),
+                access_id(Access::Viewer, "tracking", FRIEND)
+            ),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn add_editors() {
+    let decoded: MsgAddEditors = round_trip(JackalMsg::AddEditors {
+        editors: access(),
+        tracking_number: "tracking".to_string(),
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgAddEditors {
+            creator: CREATOR.to_string(),
+            editor_ids: access().ids(Access::Editor, "tracking").join(","),
+            editor_keys: access().keys().join(","),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn remove_editors() {
+    let decoded: MsgRemoveEditors = round_trip(JackalMsg::RemoveEditors {
+        editors: vec![FRIEND.to_string()],
+        tracking_number: "tracking".to_string(),
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgRemoveEditors {
+            creator: CREATOR.to_string(),
+            editor_ids: access_id(Access::Editor, "tracking", FRIEND),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn reset_editors() {
+    let decoded: MsgResetEditors = round_trip(JackalMsg::ResetEditors {
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgResetEditors {
+            creator: CREATOR.to_string(),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn reset_viewers() {
+    let decoded: MsgResetViewers = round_trip(JackalMsg::ResetViewers {
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgResetViewers {
+            creator: CREATOR.to_string(),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn change_owner() {
+    let decoded: MsgChangeOwner = round_trip(JackalMsg::ChangeOwner {
+        address: "address".to_string(),
+        owner: "owner".to_string(),
+        new_owner: "new_owner".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgChangeOwner {
+            creator: CREATOR.to_string(),
+            address: "address".to_string(),
+            file_owner: "owner".to_string(),
+            new_owner: "new_owner".to_string(),
+        }
+    );
+}
+
+#[test]
+fn delete() {
+    let decoded: MsgDangerDelete = round_trip(JackalMsg::Delete {
+        path: FilePath::new("s/home/file.txt").unwrap(),
+        account: "account".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgDangerDelete {
+            creator: CREATOR.to_string(),
+            hash_path: merkle_path("s/home/file.txt"),
+            account: "account".to_string(),
+        }
+    );
+}
+
+#[test]
+fn delete_file() {
+    let decoded: MsgDeleteFile = round_trip(JackalMsg::DeleteFile {
+        hash_path: "hash_path".to_string(),
+        account: "account".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgDeleteFile {
+            creator: CREATOR.to_string(),
+            hash_path: "hash_path".to_string(),
+            account: "account".to_string(),
+        }
+    );
+}
+
+#[test]
+fn provision_file_tree() {
+    let decoded: MsgProvisionFileTree = round_trip(JackalMsg::ProvisionFileTree {
+        editors: access(),
+        viewers: AccessMap::new(),
+        tracking_number: "tracking".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgProvisionFileTree {
+            creator: CREATOR.to_string(),
+            editors: access().to_json(Access::Editor, "tracking"),
+            viewers: "{}".to_string(),
+            tracking_number: "tracking".to_string(),
+        }
+    );
+}
+
+#[test]
+fn buy_storage() {
+    let decoded: MsgBuyStorage = round_trip(JackalMsg::BuyStorage {
+        for_address: "for_address".to_string(),
+        duration_days: 30,
+        bytes: 1_000_000_000,
+        payment_denom: "ujkl".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgBuyStorage {
+            creator: CREATOR.to_string(),
+            for_address: "for_address".to_string(),
+            duration_days: 30,
+            bytes: 1_000_000_000,
+            payment_denom: "ujkl".to_string(),
+        }
+    );
+}
+
+#[test]
+fn upgrade_storage() {
+    let decoded: MsgUpgradeStorage = round_trip(JackalMsg::UpgradeStorage {
+        for_address: "for_address".to_string(),
+        duration_days: 30,
+        bytes: 1_000_000_000,
+        payment_denom: "ujkl".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgUpgradeStorage {
+            creator: CREATOR.to_string(),
+            for_address: "for_address".to_string(),
+            duration_days: 30,
+            bytes: 1_000_000_000,
+            payment_denom: "ujkl".to_string(),
+        }
+    );
+}
+
+#[test]
+fn sign_contract() {
+    let decoded: MsgSignContract = round_trip(JackalMsg::SignContract {
+        cid: "cid".to_string(),
+        pay_once: true,
+    });
+    assert_eq!(
+        decoded,
+        MsgSignContract {
+            creator: CREATOR.to_string(),
+            cid: "cid".to_string(),
+            pay_once: true,
+        }
+    );
+}
+
+#[test]
+fn cancel_contract() {
+    let decoded: MsgCancelContract = round_trip(JackalMsg::CancelContract {
+        cid: "cid".to_string(),
+    });
+    assert_eq!(
+        decoded,
+        MsgCancelContract {
+            creator: CREATOR.to_string(),
+            cid: "cid".to_string(),
+        }
+    );
+}
+
+#[test]
+fn post_key_is_a_filetree_msg() {
+    let msg = JackalMsg::PostKey {
+        key: "key".to_string(),
+    };
+    assert_eq!(
+        msg.type_url(ProtocolVersion::V3).unwrap(),
+        "/canine_chain.filetree.MsgPostKey"
+    );
+
+    // the encoded bytes are the plain protobuf msg, not wrapped in an Any
+    let CosmosMsg::Stargate { value, .. } =
+        msg.into_cosmos_msg(ProtocolVersion::V3, CREATOR).unwrap()
+    else {
+        panic!("expected a stargate msg")
+    };
+    let expected = MsgPostKey {
+        creator: CREATOR.to_string(),
+        key: "key".to_string(),
+    };
+    assert_eq!(value.to_vec(), expected.encode_to_vec());
+}
+
+#[test]
+fn kind_serializes_to_its_name() {
+    let msg = JackalMsg::PostKey {
+        key: "key".to_string(),
+    };
+    assert_eq!(msg.kind(), JackalMsgKind::PostKey);
+    assert_eq!(
+        cosmwasm_std::to_json_string(&msg.kind()).unwrap(),
+        format!("\"{}\"", msg.kind())
+    );
+    assert_eq!(
+        JackalMsgKind::ProvisionFileTree.as_str(),
+        "provision_file_tree"
+    );
+}
+
+#[test]
+fn decode_responses() {
+    let data = MsgPostFileResponse {
+        path: merkle_path("s/home/file.txt"),
+    }
+    .encode_to_vec();
+    assert_eq!(
+        JackalMsgResponse::decode(JackalMsgKind::PostFile, &data),
+        JackalMsgResponse::PostFile {
+            path: merkle_path("s/home/file.txt")
+        }
+    );
+    assert_eq!(
+        JackalMsgResponse::decode(JackalMsgKind::PostKey, &[]),
+        JackalMsgResponse::Empty {}
+    );
+    assert_eq!(
+        JackalMsgResponse::decode(JackalMsgKind::PostKey, &[1, 2]),
+        JackalMsgResponse::Raw {
+            data: vec![1, 2].into()
+        }
+    );
+}
+
+#[test]
+fn field_tags_match_canine_chain() {
+    // `MsgMakeRootV2` and `MsgRemoveViewers` used to be declared with drifted field tags
+    let make_root = MsgMakeRootV2 {
+        creator: "c".to_string(),
+        editors: "e".to_string(),
+        viewers: "v".to_string(),
+        tracking_number: "t".to_string(),
+    };
+    assert_eq!(
+        make_root.encode_to_vec(),
+        b"\x0a\x01c\x12\x01e\x1a\x01v\x22\x01t".to_vec()
+    );
+
+    let remove_viewers = MsgRemoveViewers {
+        creator: "c".to_string(),
+        viewer_ids: "i".to_string(),
+        address: "a".to_string(),
+        file_owner: "o".to_string(),
+    };
+    assert_eq!(
+        remove_viewers.encode_to_vec(),
+        b"\x0a\x01c\x12\x01i\x1a\x01a\x22\x01o".to_vec()
+    );
+}
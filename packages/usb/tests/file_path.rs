@@ -0,0 +1,81 @@
+use cosmwasm_std::{from_json, to_json_string};
+use usb::{
+    error::FilePathError,
+    helpers::{hash_and_hex, merkle_helper, merkle_path, FilePath},
+};
+
+#[test]
+fn normalizes_trailing_slashes() {
+    let path = FilePath::new("s/home/").unwrap();
+    assert_eq!(path.as_str(), "s/home");
+    assert_eq!(path, FilePath::new("s/home").unwrap());
+    assert_eq!(FilePath::new("s/").unwrap(), FilePath::root());
+}
+
+#[test]
+fn rejects_invalid_paths() {
+    assert_eq!(FilePath::new(""), Err(FilePathError::Empty));
+    assert_eq!(FilePath::new("/"), Err(FilePathError::Empty));
+    assert_eq!(
+        FilePath::new("/s/home"),
+        Err(FilePathError::NotRooted("/s/home".to_string()))
+    );
+    assert_eq!(
+        FilePath::new("home/file.txt"),
+        Err(FilePathError::NotRooted("home/file.txt".to_string()))
+    );
+    assert_eq!(
+        FilePath::new("s//file.txt"),
+        Err(FilePathError::EmptySegment("s//file.txt".to_string()))
+    );
+}
+
+#[test]
+fn navigation() {
+    let path = FilePath::new("s/home/file.txt").unwrap();
+    assert_eq!(path.child_name(), "file.txt");
+    assert_eq!(path.parent(), Some(FilePath::new("s/home").unwrap()));
+    assert_eq!(path.parent().unwrap().parent(), Some(FilePath::root()));
+    assert_eq!(FilePath::root().parent(), None);
+    assert!(FilePath::root().is_root());
+
+    let home = FilePath::new("s/home").unwrap();
+    assert_eq!(home.join("file.txt").unwrap(), path);
+    assert_eq!(
+        home.join("a/b"),
+        Err(FilePathError::InvalidName("a/b".to_string()))
+    );
+    assert_eq!(
+        home.join(""),
+        Err(FilePathError::InvalidName(String::new()))
+    );
+
+    assert!(path.starts_with(&home));
+    assert!(home.starts_with(&home));
+    assert!(!FilePath::new("s/homework").unwrap().starts_with(&home));
+}
+
+#[test]
+fn hashes_match_the_filetree_helpers() {
+    let path = FilePath::new("s/home/file.txt").unwrap();
+    assert_eq!(path.merkle_path(), merkle_path("s/home/file.txt"));
+    assert_eq!(
+        path.hash_parent_child().unwrap(),
+        merkle_helper("s/home/file.txt")
+    );
+    assert_eq!(
+        path.hash_parent_child().unwrap(),
+        (merkle_path("s/home"), hash_and_hex("file.txt"))
+    );
+    assert_eq!(
+        FilePath::root().hash_parent_child(),
+        Err(FilePathError::NoParent)
+    );
+}
+
+#[test]
+fn validated_on_deserialize() {
+    let path: FilePath = from_json(r#""s/home/""#).unwrap();
+    assert_eq!(to_json_string(&path).unwrap(), r#""s/home""#);
+    assert!(from_json::<FilePath>(r#""home""#).is_err());
+}
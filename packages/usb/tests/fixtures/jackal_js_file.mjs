@@ -0,0 +1,47 @@
+// Encrypts a file the way jackal.js does (`convertToEncryptedFile` and `aesCrypt`),
+// with a fixed key and IV, and prints the hex encoded result.
+// Run with node >= 20: `node jackal_js_file.mjs > jackal_js_file.hex`
+import { webcrypto as crypto } from 'node:crypto'
+import { File } from 'node:buffer'
+
+const keyBytes = new Uint8Array(32).map((_, i) => i)
+const iv = new Uint8Array(16).map((_, i) => 0xf0 + i)
+
+async function aesCrypt (data, key, iv, mode) {
+  const algo = { name: 'AES-GCM', iv }
+  if (mode === 'encrypt') {
+    return new Blob([await crypto.subtle.encrypt(algo, key, await data.arrayBuffer())])
+  }
+  return new Blob([await crypto.subtle.decrypt(algo, key, await data.arrayBuffer())])
+}
+
+async function convertToEncryptedFile (workingFile, key, iv) {
+  const chunkSize = 32 * Math.pow(1024, 2) // in bytes
+  const details = {
+    name: workingFile.name,
+    lastModified: workingFile.lastModified,
+    type: workingFile.type,
+    size: workingFile.size
+  }
+  const detailsBlob = new Blob([JSON.stringify(details)])
+  const encryptedArray = [
+    new Blob([(detailsBlob.size + 16).toString().padStart(8, '0')]),
+    await aesCrypt(detailsBlob, key, iv, 'encrypt')
+  ]
+  for (let i = 0; i < workingFile.size; i += chunkSize) {
+    const blobChunk = workingFile.slice(i, i + chunkSize)
+    encryptedArray.push(
+      new Blob([(blobChunk.size + 16).toString().padStart(8, '0')]),
+      await aesCrypt(blobChunk, key, iv, 'encrypt')
+    )
+  }
+  return new Blob(encryptedArray)
+}
+
+const key = await crypto.subtle.importKey('raw', keyBytes, 'AES-GCM', true, ['encrypt', 'decrypt'])
+const file = new File(['hello jackal, from the js client'], 'hello.txt', {
+  type: 'text/plain',
+  lastModified: 1700000000000
+})
+const encrypted = await convertToEncryptedFile(file, key, iv)
+console.log(Buffer.from(await encrypted.arrayBuffer()).toString('hex'))
@@ -0,0 +1,182 @@
+use cosmwasm_std::{QueryRequest, Timestamp};
+use prost::Message;
+use usb::{
+    error::JackalError,
+    helpers::{hash_and_hex, merkle_path, FilePath},
+    types::{
+        filetree::{
+            Files, Pubkey, QueryFileRequest, QueryFileResponse, QueryPubkeyRequest,
+            QueryPubkeyResponse,
+        },
+        storage::{
+            ActiveDeals, QueryActiveDealRequest, QueryActiveDealResponse,
+            QueryStoragePaymentInfoRequest, QueryStoragePaymentInfoResponse, StoragePaymentInfo,
+        },
+        JackalQueryType,
+    },
+    versions::ProtocolVersion,
+    JackalQuery, JackalQueryResponse,
+};
+
+const OWNER: &str = "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4";
+
+/// encode `query` as a v3 stargate query and decode it back into the prost request matching its path
+fn round_trip<T: JackalQueryType + Default>(query: &JackalQuery) -> T {
+    match query.to_query_request(ProtocolVersion::V3).unwrap() {
+        QueryRequest::Stargate { path, data } => {
+            assert_eq!(path, T::PATH);
+            T::decode(data.as_slice()).unwrap()
+        }
+        other => panic!("expected a stargate query, got {other:?}"),
+    }
+}
+
+#[test]
+fn file() {
+    let query = JackalQuery::File {
+        path: FilePath::new("s/home/file.txt").unwrap(),
+        owner: OWNER.to_string(),
+    };
+    let decoded: QueryFileRequest = round_trip(&query);
+    let address = merkle_path("s/home/file.txt");
+    assert_eq!(
+        decoded,
+        QueryFileRequest {
+            owner_address: hash_and_hex(&format!("o{address}{}", hash_and_hex(OWNER))),
+            address: address.clone(),
+        }
+    );
+
+    let data = QueryFileResponse {
+        files: Some(Files {
+            address: address.clone(),
+            contents: "jklf1fid".to_string(),
+            owner: decoded.owner_address.clone(),
+            viewing_access: "{}".to_string(),
+            edit_access: "{}".to_string(),
+            tracking_number: "tracking".to_string(),
+        }),
+    }
+    .encode_to_vec();
+    assert_eq!(
+        JackalQueryResponse::decode(&query, &data),
+        JackalQueryResponse::File {
+            address,
+            contents: "jklf1fid".to_string(),
+            owner: decoded.owner_address,
+            viewing_access: "{}".to_string(),
+            edit_access: "{}".to_string(),
+            tracking_number: "tracking".to_string(),
+        }
+    );
+}
+
+#[test]
+fn pub_key() {
+    let query = JackalQuery::PubKey {
+        address: OWNER.to_string(),
+    };
+    let decoded: QueryPubkeyRequest = round_trip(&query);
+    assert_eq!(decoded.address, OWNER);
+
+    let data = QueryPubkeyResponse {
+        pubkey: Some(Pubkey {
+            address: OWNER.to_string(),
+            key: "02ab".to_string(),
+        }),
+    }
+    .encode_to_vec();
+    assert_eq!(
+        JackalQueryResponse::decode(&query, &data),
+        JackalQueryResponse::PubKey {
+            address: OWNER.to_string(),
+            key: "02ab".to_string(),
+        }
+    );
+}
+
+#[test]
+fn storage_payment_info() {
+    let query = JackalQuery::StoragePaymentInfo {
+        address: OWNER.to_string(),
+    };
+    let decoded: QueryStoragePaymentInfoRequest = round_trip(&query);
+    assert_eq!(decoded.address, OWNER);
+
+    let data = QueryStoragePaymentInfoResponse {
+        storage_payment_info: Some(StoragePaymentInfo {
+            start: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 5,
+            }),
+            end: None,
+            space_available: 1_000_000_000,
+            space_used: 12,
+            address: OWNER.to_string(),
+        }),
+    }
+    .encode_to_vec();
+    assert_eq!(
+        JackalQueryResponse::decode(&query, &data),
+        JackalQueryResponse::StoragePaymentInfo {
+            start: Timestamp::from_seconds(1_700_000_000).plus_nanos(5),
+            end: Timestamp::from_seconds(0),
+            space_available: 1_000_000_000,
+            space_used: 12,
+            address: OWNER.to_string(),
+        }
+    );
+}
+
+#[test]
+fn active_deals() {
+    let query = JackalQuery::ActiveDeals {
+        cid: "jklc1cid".to_string(),
+    };
+    let decoded: QueryActiveDealRequest = round_trip(&query);
+    assert_eq!(decoded.cid, "jklc1cid");
+
+    let data = QueryActiveDealResponse {
+        active_deals: Some(ActiveDeals {
+            cid: "jklc1cid".to_string(),
+            provider: "jkl1provider".to_string(),
+            endblock: "100".to_string(),
+            ..ActiveDeals::default()
+        }),
+    }
+    .encode_to_vec();
+    match JackalQueryResponse::decode(&query, &data) {
+        JackalQueryResponse::ActiveDeals {
+            cid,
+            provider,
+            end_block,
+            ..
+        } => assert_eq!(
+            (cid.as_str(), provider.as_str(), end_block.as_str()),
+            ("jklc1cid", "jkl1provider", "100")
+        ),
+        other => panic!("expected active deals, got {other:?}"),
+    }
+
+    // storage deals don't exist on v4
+    assert_eq!(
+        query.to_query_request(ProtocolVersion::V4).unwrap_err(),
+        JackalError::UnsupportedQuery {
+            query: "active_deals".to_string(),
+            version: ProtocolVersion::V4,
+        }
+    );
+}
+
+#[test]
+fn undecodable_responses_are_raw() {
+    let query = JackalQuery::PubKey {
+        address: OWNER.to_string(),
+    };
+    assert_eq!(
+        JackalQueryResponse::decode(&query, &[1, 2]),
+        JackalQueryResponse::Raw {
+            data: vec![1, 2].into()
+        }
+    );
+}
@@ -0,0 +1,82 @@
+use cosmwasm_std::CosmosMsg;
+use usb::{
+    error::JackalError, helpers::AccessMap, versions::ProtocolVersion, JackalMsg, JackalMsgKind,
+};
+
+const CREATOR: &str = "jkl1tyl97ac3s7sec4jwznk0s7n3tlwf3math03qj4";
+
+fn make_root() -> JackalMsg {
+    JackalMsg::MakeRoot {
+        editors: AccessMap::new().with(CREATOR, "key"),
+        viewers: AccessMap::new().with(CREATOR, "key"),
+        tracking_number: "tracking".to_string(),
+    }
+}
+
+fn encoded_type_url(msg: JackalMsg, version: ProtocolVersion) -> String {
+    match msg.into_cosmos_msg(version, CREATOR).unwrap() {
+        CosmosMsg::Stargate { type_url, .. } => type_url,
+        other => panic!("expected a stargate msg, got {other:?}"),
+    }
+}
+
+#[test]
+fn make_root_follows_the_protocol_version() {
+    assert_eq!(
+        encoded_type_url(make_root(), ProtocolVersion::V3),
+        "/canine_chain.filetree.MsgMakeRootV2"
+    );
+    assert_eq!(
+        encoded_type_url(make_root(), ProtocolVersion::V4),
+        "/canine_chain.filetree.MsgProvisionFileTree"
+    );
+}
+
+#[test]
+fn shared_msgs_keep_their_type_url() {
+    // spelled out rather than read from the generated types, so regenerating them can't move a msg unnoticed
+    let shared = [
+        (
+            JackalMsg::PostKey {
+                key: "02ab".to_string(),
+            },
+            "/canine_chain.filetree.MsgPostKey",
+        ),
+        (
+            JackalMsg::BuyStorage {
+                for_address: CREATOR.to_string(),
+                duration_days: 30,
+                bytes: 1_000_000_000,
+                payment_denom: "ujkl".to_string(),
+            },
+            "/canine_chain.storage.MsgBuyStorage",
+        ),
+    ];
+    for (msg, type_url) in shared {
+        for version in [ProtocolVersion::V3, ProtocolVersion::V4] {
+            assert_eq!(msg.type_url(version).as_deref(), Ok(type_url));
+            assert_eq!(encoded_type_url(msg.clone(), version), type_url);
+        }
+    }
+}
+
+#[test]
+fn v4_rejects_storage_contracts() {
+    let sign = JackalMsg::SignContract {
+        cid: "cid".to_string(),
+        pay_once: false,
+    };
+    let expected = JackalError::UnsupportedMsg {
+        msg: JackalMsgKind::SignContract,
+        version: ProtocolVersion::V4,
+    };
+    assert_eq!(sign.type_url(ProtocolVersion::V4), Err(expected.clone()));
+    assert_eq!(
+        sign.into_cosmos_msg(ProtocolVersion::V4, CREATOR),
+        Err(expected.clone())
+    );
+    assert_eq!(
+        expected.to_string(),
+        "sign_contract is not supported by canine-chain v4"
+    );
+}
@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+
+# Regenerates the canine-chain prost types of the usb package from ./packages/usb/proto (see its README.md)
+set -eu
+
+cargo run --quiet --package usb-proto-build
+rustfmt --edition 2021 packages/usb/src/types/proto/*.rs